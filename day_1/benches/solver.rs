@@ -0,0 +1,48 @@
+use day_1::puzzle_engine::solve_puzzle_cached;
+use day_1::{DialConfig, SolveResult, solve_puzzle};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SAMPLES: u32 = 20;
+
+fn generate_input(rotations: usize, distance: i64) -> String {
+    (0..rotations)
+        .map(|i| {
+            let direction = if i % 3 == 0 { 'L' } else { 'R' };
+            format!("{}{}\n", direction, distance + i as i64 % 7)
+        })
+        .collect()
+}
+
+fn bench(group: &str, name: &str, input: &str) {
+    bench_with(group, name, input, solve_puzzle);
+}
+
+fn bench_with(group: &str, name: &str, input: &str, solve: fn(&str, &DialConfig) -> SolveResult) {
+    let config = DialConfig::default();
+    let mut total = Duration::ZERO;
+
+    for _ in 0..SAMPLES {
+        let started = Instant::now();
+        black_box(solve(black_box(input), &config));
+        total += started.elapsed();
+    }
+
+    println!("{}/{:<24} {:>12.3?} per run", group, name, total / SAMPLES);
+}
+
+fn main() {
+    for rotations in [1_000, 10_000, 100_000] {
+        let input = generate_input(rotations, 5);
+        bench("many_small", &format!("{} rotations", rotations), &input);
+    }
+
+    for distance in [1_000_000, 1_000_000_000, 1_000_000_000_000] {
+        let input = generate_input(10, distance);
+        bench("few_enormous", &format!("distance {}", distance), &input);
+    }
+
+    let repetitive = "R17\n".repeat(100_000);
+    bench_with("repetitive", "uncached", &repetitive, solve_puzzle);
+    bench_with("repetitive", "cached", &repetitive, solve_puzzle_cached);
+}
@@ -0,0 +1,745 @@
+use crate::puzzle_engine::{ConfigError, DialConfig, Direction, Step, solve_puzzle, walk};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+pub fn zeros_by_line(input: &str, config: &DialConfig) -> Vec<(usize, u64)> {
+    let mut lines: Vec<(usize, u64)> = Vec::new();
+
+    walk(input, config, |step| {
+        if step.zeros == 0 {
+            return;
+        }
+
+        match lines.last_mut() {
+            Some((line_number, zeros)) if *line_number == step.line_number => *zeros += step.zeros,
+            _ => lines.push((step.line_number, step.zeros)),
+        }
+    });
+
+    lines
+}
+
+// Entry `i` is the Part 2 count after the first `i` applied rotations, so the
+// first entry is always 0 and the last is the full `zero_count`.
+pub fn prefix_zero_counts(input: &str, config: &DialConfig) -> Vec<u64> {
+    let mut counts = vec![0u64];
+
+    walk(input, config, |step| {
+        let last = counts[counts.len() - 1];
+        counts.push(last.saturating_add(step.zeros));
+    });
+
+    counts
+}
+
+// The first position a rotation ends on twice, with the line numbers of the
+// earlier and the later rotation. The start does not count until a rotation
+// ends there.
+pub fn first_revisit(input: &str, config: &DialConfig) -> Option<(i32, usize, usize)> {
+    let mut first_seen = HashMap::new();
+    let mut revisit = None;
+
+    walk(input, config, |step| {
+        if revisit.is_some() {
+            return;
+        }
+        if let Some(&first_line) = first_seen.get(&step.to) {
+            revisit = Some((step.to, first_line, step.line_number));
+        } else {
+            first_seen.insert(step.to, step.line_number);
+        }
+    });
+
+    revisit
+}
+
+// Like the trace from `solve_with_trace`, but with `config.start` first, so
+// entry `i` is the position after the first `i` applied rotations.
+pub fn position_timeline(input: &str, config: &DialConfig) -> Vec<i32> {
+    let mut timeline = vec![config.start];
+    walk(input, config, |step| timeline.push(step.to));
+    timeline
+}
+
+// Whether some rotation leaves the dial on `config.start` again. Jumps from
+// RESET or SET lines are not rotations, so they never count.
+pub fn returns_to_start(input: &str, config: &DialConfig) -> bool {
+    let mut returned = false;
+    walk(input, config, |step| returned |= step.to == config.start);
+    returned
+}
+
+// Finds where the positions after each rotation settle into a repeating
+// pattern: `(start, length)` means that from rotation index `start` (0-based)
+// to the end, every position equals the one `length` rotations later, and the
+// pattern repeats at least twice. The longest such tail wins, then the
+// shortest period.
+pub fn detect_cycle(input: &str, config: &DialConfig) -> Option<(usize, usize)> {
+    let mut trace = Vec::new();
+    walk(input, config, |step| trace.push(step.to));
+    trace.reverse();
+
+    // Tails of the trace are prefixes of `trace` reversed; the KMP prefix
+    // function gives the shortest period of every prefix in one pass.
+    let mut border = vec![0; trace.len()];
+    let mut best = None;
+
+    for i in 1..trace.len() {
+        let mut k = border[i - 1];
+        while k > 0 && trace[i] != trace[k] {
+            k = border[k - 1];
+        }
+        if trace[i] == trace[k] {
+            k += 1;
+        }
+        border[i] = k;
+
+        let (len, period) = (i + 1, i + 1 - k);
+        if len >= 2 * period {
+            best = Some((trace.len() - len, period));
+        }
+    }
+
+    best
+}
+
+// Lines with a rotation of a whole number of turns, which ends where it
+// started. These are only flagged: they still count every zero on the way.
+pub fn noop_rotations(input: &str, config: &DialConfig) -> Vec<usize> {
+    let mut lines: Vec<usize> = Vec::new();
+
+    walk(input, config, |step| {
+        if step.distance % i64::from(step.size) == 0 && lines.last() != Some(&step.line_number) {
+            lines.push(step.line_number);
+        }
+    });
+
+    lines
+}
+
+// The line of the single rotation that crossed zero most often; ties go to the
+// earliest line.
+pub fn max_zero_rotation(input: &str, config: &DialConfig) -> Option<(usize, u32)> {
+    let mut best: Option<(usize, u64)> = None;
+
+    walk(input, config, |step| {
+        if step.zeros > best.map_or(0, |(_, zeros)| zeros) {
+            best = Some((step.line_number, step.zeros));
+        }
+    });
+
+    best.map(|(line_number, zeros)| (line_number, u32::try_from(zeros).unwrap_or(u32::MAX)))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TravelStats {
+    pub net_offset: i128,
+    pub farthest_offset: i128,
+}
+
+// Tracks the unwrapped offset from the start, ignoring the dial's modulo. Like
+// `SolveResult::net_offset` it is an i128, which no run can overflow.
+pub fn travel_stats(input: &str, config: &DialConfig) -> TravelStats {
+    let mut stats = TravelStats::default();
+
+    walk(input, config, |step| {
+        stats.net_offset += i128::from(step.direction.sign()) * i128::from(step.distance);
+
+        if stats.net_offset.abs() > stats.farthest_offset.abs() {
+            stats.farthest_offset = stats.net_offset;
+        }
+    });
+
+    stats
+}
+
+// The signed clicks travelled (`R` positive, saturating at the i64 range) and
+// where that leaves the dial, so `(start + signed) mod size` is the wrapped
+// position. RESET and SET jump without travelling, which breaks that identity.
+pub fn displacement(input: &str, config: &DialConfig) -> (i64, i32) {
+    let result = solve_puzzle(input, config);
+    let signed = result
+        .net_offset
+        .clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64;
+    (signed, result.final_position)
+}
+
+// Full revolutions touch every position once, so only the remainder is stepped.
+// Counts saturate at u64::MAX, as a few near-i64::MAX rotations can pass it.
+// After a DIAL line clicks land on the new dial's positions, so the histogram
+// is as long as the largest dial.
+pub fn position_histogram(input: &str, config: &DialConfig) -> Vec<u64> {
+    let mut histogram = vec![0u64; config.size as usize];
+    let mut full_revolutions: BTreeMap<u32, u64> = BTreeMap::new();
+
+    walk(input, config, |step| {
+        let size = i64::from(step.size);
+        if histogram.len() < step.size as usize {
+            histogram.resize(step.size as usize, 0);
+        }
+        let turns = full_revolutions.entry(step.size).or_default();
+        *turns = turns.saturating_add((step.distance / size) as u64);
+
+        let sign = step.direction.sign();
+        for click in 1..=step.distance % size {
+            let position = (i64::from(step.from) + sign * click).rem_euclid(size);
+            histogram[position as usize] = histogram[position as usize].saturating_add(1);
+        }
+    });
+
+    for (size, turns) in full_revolutions {
+        for count in &mut histogram[..size as usize] {
+            *count = count.saturating_add(turns);
+        }
+    }
+
+    histogram
+}
+
+// The dial moves one position per click, so on any dial larger than one
+// position two consecutive clicks can never both land on the target. Only a
+// single-position dial, where every click lands on it, builds longer streaks,
+// and a streak carries across rotations only while every click keeps landing.
+pub fn longest_zero_streak(input: &str, config: &DialConfig) -> u32 {
+    let mut longest = 0u64;
+    let mut current = 0u64;
+
+    walk(input, config, |step| {
+        let (distance, size) = (step.distance as u64, u64::from(step.size));
+        let first_hit = first_hit(step, config);
+        if distance < first_hit {
+            current = 0;
+            return;
+        }
+
+        // The clicks that land in a row from the first one, continuing the streak.
+        let leading = match (first_hit, size) {
+            (1, 1) => distance,
+            (1, _) => 1,
+            _ => 0,
+        };
+        current = current.saturating_add(leading);
+        longest = longest.max(current).max(1);
+        if leading < distance {
+            current = u64::from((distance - first_hit).is_multiple_of(size));
+        }
+    });
+
+    u32::try_from(longest).unwrap_or(u32::MAX)
+}
+
+// Includes the start position.
+pub fn visited_positions(input: &str, config: &DialConfig) -> BTreeSet<i32> {
+    let mut visited = clicked_positions(input, config);
+    visited.insert(config.start);
+    visited
+}
+
+// Sorted positions no click lands on. Unlike `visited_positions`, the start
+// only counts as visited if a click lands there.
+pub fn unvisited_positions(input: &str, config: &DialConfig) -> Vec<i32> {
+    let clicked = clicked_positions(input, config);
+    (0..config.size as i32)
+        .filter(|position| !clicked.contains(position))
+        .collect()
+}
+
+// A rotation of a full turn or more lands on every position, so only shorter
+// ones are stepped click by click.
+fn clicked_positions(input: &str, config: &DialConfig) -> BTreeSet<i32> {
+    let mut visited = BTreeSet::new();
+
+    walk(input, config, |step| {
+        let size = i64::from(step.size);
+        let every = 0..step.size as i32;
+        // Exactly the whole dial, and none of a larger one from before a DIAL.
+        if visited.len() == step.size as usize && visited.last().is_some_and(|p| every.contains(p))
+        {
+            return;
+        }
+        if step.distance >= size {
+            visited.extend(every);
+            return;
+        }
+
+        let sign = step.direction.sign();
+        for click in 1..=step.distance {
+            visited.insert((i64::from(step.from) + sign * click).rem_euclid(size) as i32);
+        }
+    });
+
+    visited
+}
+
+// Entry `p` is the number of the click that first reached position `p`,
+// counting from 1 across the whole run, or `None` if none did. The start is
+// click 0. A rotation reaches every position within its first `size` clicks,
+// so no more of it is stepped. As with `position_histogram`, DIAL lines can
+// lengthen the result to the largest dial. The config is validated first, as
+// the start has to be on the dial.
+pub fn first_visit_indices(
+    input: &str,
+    config: &DialConfig,
+) -> Result<Vec<Option<u64>>, ConfigError> {
+    config.validate()?;
+    let mut first_visits = vec![None; config.size as usize];
+    first_visits[config.start as usize] = Some(0);
+    let mut unvisited = first_visits.len() - 1;
+    let mut clicks_before = 0u64;
+
+    walk(input, config, |step| {
+        let size = i64::from(step.size);
+        if first_visits.len() < step.size as usize {
+            unvisited += step.size as usize - first_visits.len();
+            first_visits.resize(step.size as usize, None);
+        }
+        let sign = step.direction.sign();
+        for click in 1..=step.distance.min(size) {
+            if unvisited == 0 {
+                break;
+            }
+            let position = (i64::from(step.from) + sign * click).rem_euclid(size) as usize;
+            if first_visits[position].is_none() {
+                first_visits[position] = Some(clicks_before + click as u64);
+                unvisited -= 1;
+            }
+        }
+        clicks_before = clicks_before.saturating_add(step.distance as u64);
+    });
+
+    Ok(first_visits)
+}
+
+// Clicks are numbered from 1 across the whole run; the window is inclusive.
+pub fn zeros_in_window(input: &str, config: &DialConfig, start_click: u64, end_click: u64) -> u32 {
+    let mut clicks_before = 0u64;
+    let mut zeros = 0u64;
+
+    if start_click > end_click {
+        return 0;
+    }
+
+    walk(input, config, |step| {
+        let distance = step.distance as u64;
+        let first = clicks_before.saturating_add(1);
+        let last = clicks_before.saturating_add(distance);
+        clicks_before = last;
+
+        let (from, to) = (start_click.max(first), end_click.min(last));
+        if from > to {
+            return;
+        }
+
+        let first_hit = first_hit(step, config);
+        // `first - 1` clicks came before this rotation, and `click` is at least that.
+        let hits_up_to = |click: u64| match (click - (first - 1)).checked_sub(first_hit) {
+            Some(past) => past / u64::from(step.size) + 1,
+            None => 0,
+        };
+        zeros = zeros.saturating_add(hits_up_to(to) - hits_up_to(from - 1));
+    });
+
+    u32::try_from(zeros).unwrap_or(u32::MAX)
+}
+
+// Within a rotation the target is reached after this many clicks and then
+// every `step.size` clicks.
+fn first_hit(step: &Step, config: &DialConfig) -> u64 {
+    let size = u64::from(step.size);
+    let gap = match step.direction {
+        Direction::Left => i64::from(step.from) - i64::from(config.target),
+        Direction::Right => i64::from(config.target) - i64::from(step.from),
+    };
+
+    match gap.rem_euclid(size as i64) as u64 {
+        0 => size,
+        gap => gap,
+    }
+}
+
+// Splits the Part 2 zeros into `(even, odd)` by the number of the click that
+// reached the target, counting clicks from 1 across the whole run. A start
+// counted by `count_initial` is click 0, so it is even.
+pub fn zeros_by_parity(input: &str, config: &DialConfig) -> (u64, u64) {
+    let mut clicks_before = 0u64;
+    let mut counts = [
+        u64::from(config.count_initial && config.start == config.target),
+        0,
+    ];
+
+    walk(input, config, |step| {
+        let size = u64::from(step.size);
+        let distance = step.distance as u64;
+        let first = first_hit(step, config);
+
+        if first <= distance {
+            let hits = (distance - first) / size + 1;
+            let parity = ((clicks_before % 2 + first % 2) % 2) as usize;
+            // On an even dial every hit has the same parity; on an odd one they alternate.
+            if size % 2 == 0 {
+                counts[parity] += hits;
+            } else {
+                counts[parity] += hits.div_ceil(2);
+                counts[1 - parity] += hits / 2;
+            }
+        }
+        clicks_before = clicks_before.saturating_add(distance);
+    });
+
+    (counts[0], counts[1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE_INPUT: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn zero_lines_use_original_line_numbers() {
+        let input = "L68\n\nL30\nR48\n# comment\nL5 R60\nL55\n";
+
+        assert_eq!(
+            zeros_by_line(input, &DialConfig::default()),
+            vec![(1, 1), (4, 1), (6, 1), (7, 1)]
+        );
+    }
+
+    #[test]
+    fn prefix_counts_rise_to_the_answer() {
+        let config = DialConfig::default();
+        let input = crate::generator::generate(400, 95);
+        let counts = prefix_zero_counts(&input, &config);
+        let result = crate::solve_puzzle(&input, &config);
+
+        assert_eq!(counts.len(), result.rotations_applied + 1);
+        assert!(counts.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(counts.last(), Some(&result.zero_count));
+        assert_eq!(prefix_zero_counts("", &config), [0]);
+    }
+
+    #[test]
+    fn timeline_starts_at_the_start() {
+        let config = DialConfig::default();
+        let timeline = position_timeline(EXAMPLE_INPUT, &config);
+        let (result, trace) = crate::solve_with_trace(EXAMPLE_INPUT, &config);
+
+        assert_eq!(timeline.len(), result.rotations_applied + 1);
+        assert_eq!(timeline[0], config.start);
+        assert_eq!(timeline[1..], trace);
+        assert_eq!(timeline.last(), Some(&result.final_position));
+        assert_eq!(position_timeline("", &config), [50]);
+    }
+
+    #[test]
+    fn returns_to_start_checks_every_rotation() {
+        let config = DialConfig::default();
+
+        assert!(returns_to_start("R20\nL30\nR10\nR5\n", &config));
+        assert!(returns_to_start("L7 R107\n", &config));
+        assert!(!returns_to_start(EXAMPLE_INPUT, &config));
+        assert!(!returns_to_start("R5\nRESET\n", &config));
+        assert!(!returns_to_start("", &config));
+    }
+
+    #[test]
+    fn displacement_keeps_whole_turns() {
+        let config = DialConfig::default();
+        let input = "R250\nR1000 L30\nL5\nR340\n";
+        let (signed, wrapped) = displacement(input, &config);
+
+        assert_eq!(signed, 1555);
+        assert_eq!(wrapped, crate::solve_puzzle(input, &config).final_position);
+        assert_eq!(
+            (i64::from(config.start) + signed).rem_euclid(i64::from(config.size)),
+            i64::from(wrapped)
+        );
+        assert_eq!(displacement("L1250\n", &config), (-1250, 0));
+        assert_eq!(displacement("", &config), (0, 50));
+    }
+
+    #[test]
+    fn zeros_split_by_click_parity() {
+        let config = DialConfig {
+            size: 5,
+            start: 0,
+            ..DialConfig::default()
+        };
+
+        // Zeros on clicks 5, 10, 15 and 20.
+        assert_eq!(zeros_by_parity("R7\nR3\nR10\n", &config), (2, 2));
+        let counted = DialConfig {
+            count_initial: true,
+            ..config
+        };
+        assert_eq!(zeros_by_parity("R7\nR3\nR10\n", &counted), (3, 2));
+
+        let config = DialConfig::default();
+        let (even, odd) = zeros_by_parity(EXAMPLE_INPUT, &config);
+        assert_eq!(
+            even + odd,
+            crate::solve_puzzle(EXAMPLE_INPUT, &config).zero_count
+        );
+        // Starting on 50, the dial can only reach 0 after an even number of clicks.
+        assert_eq!(zeros_by_parity("R250\nL1 R1\n", &config), (4, 0));
+        let odd_start = DialConfig {
+            start: 49,
+            ..config
+        };
+        assert_eq!(zeros_by_parity("R51\nL1 R1\n", &odd_start), (0, 2));
+    }
+
+    #[test]
+    fn first_revisit_reports_both_lines() {
+        let config = DialConfig::default();
+
+        assert_eq!(first_revisit(EXAMPLE_INPUT, &config), Some((0, 3, 6)));
+        assert_eq!(first_revisit("R10\n\nR5 L5\n", &config), Some((60, 1, 3)));
+        assert_eq!(first_revisit("R10\nR10\nL100\n", &config), Some((70, 2, 3)));
+        assert_eq!(first_revisit("R10\nR20\nL5\n", &config), None);
+        assert_eq!(first_revisit("R50 L50\n", &config), None);
+    }
+
+    #[test]
+    fn first_visits_are_numbered_by_click() {
+        let config = DialConfig::default();
+        let visits = first_visit_indices("R3\nL5\nL300\n", &config).unwrap();
+
+        assert_eq!(visits.len(), 100);
+        assert_eq!(visits[50], Some(0));
+        assert_eq!(visits[53], Some(3));
+        assert_eq!(visits[48], Some(8));
+        assert_eq!(visits[47], Some(9));
+        assert_eq!(visits[54], Some(102));
+
+        let short = first_visit_indices("R2\n", &config).unwrap();
+        assert_eq!(short[52], Some(2));
+        assert_eq!(short[49], None);
+        assert_eq!(short.iter().flatten().count(), 3);
+
+        let off_the_dial = DialConfig {
+            start: 100,
+            ..config
+        };
+        assert_eq!(
+            first_visit_indices("R2\n", &off_the_dial),
+            Err(ConfigError::StartOutOfRange {
+                start: 100,
+                size: 100
+            })
+        );
+    }
+
+    #[test]
+    fn detects_a_repeating_tail() {
+        let config = DialConfig::default();
+
+        let input = "R10\nR20\n".to_string() + &"R30 L30\n".repeat(3);
+        assert_eq!(detect_cycle(&input, &config), Some((1, 2)));
+
+        let input = "R1\nL1\n".to_string() + &"R25\n".repeat(9);
+        assert_eq!(detect_cycle(&input, &config), Some((1, 4)));
+
+        assert_eq!(detect_cycle("R1\nR2\nR3\n", &config), None);
+        assert_eq!(detect_cycle("", &config), None);
+    }
+
+    #[test]
+    fn whole_turns_are_flagged_but_still_counted() {
+        let config = DialConfig::default();
+        let input = "R100\nL30\nR0\nL300 R200\nR150\n";
+
+        assert_eq!(noop_rotations(input, &config), [1, 4]);
+        assert_eq!(crate::solve_puzzle("R100\n", &config).zero_count, 1);
+    }
+
+    #[test]
+    fn max_zero_rotation_finds_the_largest_crossing() {
+        let config = DialConfig::default();
+
+        assert_eq!(
+            max_zero_rotation("R50\nL5\nR1000\nL200\nR600\n", &config),
+            Some((3, 10))
+        );
+        assert_eq!(max_zero_rotation("R50\nL100\n", &config), Some((1, 1)));
+        assert_eq!(max_zero_rotation("R10\nL20\n", &config), None);
+    }
+
+    #[test]
+    fn travel_extreme_can_be_negative() {
+        let stats = travel_stats("R30\nL250\nR100\n", &DialConfig::default());
+
+        assert_eq!(stats.net_offset, -120);
+        assert_eq!(stats.farthest_offset, -220);
+    }
+
+    #[test]
+    fn travel_past_the_i64_range_does_not_overflow() {
+        let config = DialConfig::default();
+        let right = format!("R{}\n", i64::MAX).repeat(3);
+        let left = format!("L{}\n", i64::MAX).repeat(2);
+
+        assert_eq!(
+            travel_stats(&right, &config).net_offset,
+            3 * i128::from(i64::MAX)
+        );
+        assert_eq!(
+            travel_stats(&left, &config).farthest_offset,
+            -2 * i128::from(i64::MAX)
+        );
+    }
+
+    #[test]
+    fn histogram_counts_every_click() {
+        let config = DialConfig {
+            size: 10,
+            start: 5,
+            ..DialConfig::default()
+        };
+        let input = "R3\nL4\nR12\n";
+
+        let histogram = position_histogram(input, &config);
+
+        assert_eq!(histogram, vec![1, 1, 1, 1, 2, 3, 4, 3, 2, 1]);
+        assert_eq!(histogram.iter().sum::<u64>(), 3 + 4 + 12);
+        assert_eq!(histogram[0], crate::solve_puzzle(input, &config).zero_count);
+    }
+
+    #[test]
+    fn histogram_counts_saturate() {
+        let single = DialConfig {
+            size: 1,
+            start: 0,
+            ..DialConfig::default()
+        };
+        let longest = format!("R{}\n", i64::MAX);
+
+        assert_eq!(
+            position_histogram(&longest.repeat(3), &single),
+            vec![u64::MAX]
+        );
+        assert_eq!(
+            position_histogram(&longest.repeat(250), &DialConfig::default()),
+            vec![u64::MAX; 100]
+        );
+    }
+
+    #[test]
+    fn zero_streaks() {
+        let config = DialConfig::default();
+        assert_eq!(longest_zero_streak("", &config), 0);
+        assert_eq!(longest_zero_streak("R10\nL20\n", &config), 0);
+        assert_eq!(longest_zero_streak("R50\nL1\nR1\n", &config), 1);
+        assert_eq!(longest_zero_streak("R50\nR10\nL10\n", &config), 1);
+
+        let single = DialConfig {
+            size: 1,
+            start: 0,
+            ..DialConfig::default()
+        };
+        assert_eq!(longest_zero_streak("R3\nL0\nL4\n", &single), 7);
+        // The clicks on the larger dial in between miss, ending the first streak.
+        assert_eq!(
+            longest_zero_streak("DIAL 1\nR3\nDIAL 100\nR5\nDIAL 1\nR2\n", &config),
+            3
+        );
+        assert_eq!(
+            longest_zero_streak(&format!("R{}", i64::MAX), &single),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn zero_windows_span_rotation_boundaries() {
+        let config = DialConfig::default();
+        // R60 hits zero on click 50, L120 on clicks 70 and 170 overall.
+        let input = "R60\nL120\n";
+
+        assert_eq!(zeros_in_window(input, &config, 1, 180), 3);
+        assert_eq!(zeros_in_window(input, &config, 50, 50), 1);
+        assert_eq!(zeros_in_window(input, &config, 51, 69), 0);
+        assert_eq!(zeros_in_window(input, &config, 45, 75), 2);
+        assert_eq!(zeros_in_window(input, &config, 170, 170), 1);
+
+        let total = crate::solve_puzzle(EXAMPLE_INPUT, &config).zero_count as u32;
+        assert_eq!(zeros_in_window(EXAMPLE_INPUT, &config, 1, u64::MAX), total);
+
+        // These rotations end exactly on click u64::MAX, 65 clicks after a zero.
+        let longest = format!("R{}\nR{}\nR1\n", i64::MAX, i64::MAX);
+        assert_eq!(
+            zeros_in_window(&longest, &config, u64::MAX - 200, u64::MAX),
+            2
+        );
+        assert_eq!(zeros_in_window(&longest, &config, u64::MAX, u64::MAX), 0);
+        let saturated = format!("R{}\n", i64::MAX).repeat(3);
+        assert_eq!(zeros_in_window(&saturated, &config, u64::MAX, u64::MAX), 0);
+    }
+
+    #[test]
+    fn click_analyses_follow_dial_lines() {
+        let config = DialConfig::default();
+        let input = "R30\nDIAL 10\nR10\nL23\nDIAL 1000\nR2000\n";
+        let result = crate::solve_puzzle(input, &config);
+        assert_eq!(result.zero_count, 5);
+
+        assert_eq!(zeros_in_window("DIAL 10\nR10\n", &config, 1, 100), 1);
+        assert_eq!(
+            zeros_in_window(input, &config, 1, u64::MAX),
+            result.zero_count as u32
+        );
+        let (even, odd) = zeros_by_parity(input, &config);
+        assert_eq!(even + odd, result.zero_count);
+
+        let histogram = position_histogram(input, &config);
+        assert_eq!(histogram.len(), 1000);
+        assert_eq!(histogram[0], result.zero_count);
+        assert_eq!(histogram.iter().sum::<u64>(), result.clicks);
+
+        let first_visits = first_visit_indices(input, &config).unwrap();
+        assert_eq!(first_visits.len(), 1000);
+        assert!(first_visits.iter().all(Option::is_some));
+        assert_eq!(noop_rotations(input, &config), vec![3, 6]);
+    }
+
+    #[test]
+    fn out_of_range_windows_are_empty() {
+        let config = DialConfig::default();
+
+        assert_eq!(zeros_in_window("R60\n", &config, 61, 1_000), 0);
+        assert_eq!(zeros_in_window("R60\n", &config, 50, 10), 0);
+        assert_eq!(zeros_in_window("", &config, 0, 10), 0);
+    }
+
+    #[test]
+    fn visited_positions_cover_the_dial() {
+        let config = DialConfig::default();
+
+        let full = visited_positions("R5\nL130\n", &config);
+        assert_eq!(full.len(), config.size as usize);
+
+        let few = visited_positions("R3\nL5\n", &config);
+        assert_eq!(few, (48..=53).collect());
+
+        assert_eq!(visited_positions("", &config), BTreeSet::from([50]));
+        assert_eq!(visited_positions("L51\nR1\n", &config).len(), 52);
+    }
+
+    #[test]
+    fn unvisited_positions_are_the_untouched_ones() {
+        let config = DialConfig {
+            size: 10,
+            start: 5,
+            ..DialConfig::default()
+        };
+
+        assert_eq!(
+            unvisited_positions("R2\nL1\n", &config),
+            [0, 1, 2, 3, 4, 5, 8, 9]
+        );
+        assert_eq!(
+            unvisited_positions("R2\nL2\n", &config),
+            [0, 1, 2, 3, 4, 8, 9]
+        );
+        assert_eq!(unvisited_positions("", &config).len(), 10);
+        assert!(unvisited_positions("L10\n", &config).is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+use crate::puzzle_engine::{DialConfig, SolveResult, solve_continuing};
+use std::fmt;
+use std::str::FromStr;
+
+// A resumable snapshot of a Part 2 run, written as `key=value` pairs, e.g.
+// `position=32 zero_count=6 rotations_applied=10 dial_size=100`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SolverState {
+    pub position: i32,
+    pub zero_count: u64,
+    pub rotations_applied: usize,
+    // The size a `DIAL` line may have changed the dial to.
+    pub dial_size: u32,
+}
+
+impl SolverState {
+    pub fn new(config: &DialConfig) -> Self {
+        SolverState {
+            position: config.start,
+            zero_count: 0,
+            rotations_applied: 0,
+            dial_size: config.size,
+        }
+    }
+}
+
+impl From<&SolveResult> for SolverState {
+    fn from(result: &SolveResult) -> Self {
+        SolverState {
+            position: result.final_position,
+            zero_count: result.zero_count,
+            rotations_applied: result.rotations_applied,
+            dial_size: result.dial_size,
+        }
+    }
+}
+
+impl fmt::Display for SolverState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "position={} zero_count={} rotations_applied={} dial_size={}",
+            self.position, self.zero_count, self.rotations_applied, self.dial_size
+        )
+    }
+}
+
+impl FromStr for SolverState {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let mut position = None;
+        let mut zero_count = None;
+        let mut rotations_applied = None;
+        let mut dial_size = None;
+
+        for field in s.split_whitespace() {
+            let invalid = || format!("Invalid checkpoint field '{}'", field);
+            let (key, value) = field.split_once('=').ok_or_else(invalid)?;
+
+            match key {
+                "position" => position = Some(value.parse().map_err(|_| invalid())?),
+                "zero_count" => zero_count = Some(value.parse().map_err(|_| invalid())?),
+                "rotations_applied" => {
+                    rotations_applied = Some(value.parse().map_err(|_| invalid())?)
+                }
+                "dial_size" => dial_size = Some(value.parse().map_err(|_| invalid())?),
+                _ => return Err(invalid()),
+            }
+        }
+
+        match (position, zero_count, rotations_applied, dial_size) {
+            (Some(position), Some(zero_count), Some(rotations_applied), Some(dial_size)) => {
+                Ok(SolverState {
+                    position,
+                    zero_count,
+                    rotations_applied,
+                    dial_size,
+                })
+            }
+            _ => Err(format!("Incomplete checkpoint '{}'", s)),
+        }
+    }
+}
+
+pub fn solve_resuming(input: &str, config: &DialConfig, state: SolverState) -> SolverState {
+    let earlier = SolveResult {
+        final_position: state.position,
+        zero_count: state.zero_count,
+        passes_through_zero: state.zero_count,
+        rotations_applied: state.rotations_applied,
+        dial_size: state.dial_size,
+        ..SolveResult::default()
+    };
+
+    SolverState::from(&solve_continuing(input, config, earlier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::generate;
+    use crate::solve_puzzle;
+
+    #[test]
+    fn resuming_from_a_checkpoint_matches_one_pass() {
+        let config = DialConfig::default();
+        let input = generate(1_000, 58);
+        let lines: Vec<&str> = input.lines().collect();
+        let whole = SolverState::from(&solve_puzzle(&input, &config));
+
+        for split in [0, 1, 377, 999, 1_000] {
+            let (first, second) = lines.split_at(split);
+
+            let halfway = solve_resuming(&first.join("\n"), &config, SolverState::new(&config));
+            let checkpoint: SolverState = halfway.to_string().parse().unwrap();
+            let resumed = solve_resuming(&second.join("\n"), &config, checkpoint);
+
+            assert_eq!(resumed, whole, "split at line {}", split);
+        }
+    }
+
+    #[test]
+    fn resuming_keeps_the_size_set_before_the_checkpoint() {
+        let config = DialConfig::default();
+        let input = "R10\nDIAL 200\nR150\nL30\nR80";
+        let lines: Vec<&str> = input.lines().collect();
+        let whole = SolverState::from(&solve_puzzle(input, &config));
+
+        for split in 2..lines.len() {
+            let (first, second) = lines.split_at(split);
+
+            let halfway = solve_resuming(&first.join("\n"), &config, SolverState::new(&config));
+            let checkpoint: SolverState = halfway.to_string().parse().unwrap();
+            let resumed = solve_resuming(&second.join("\n"), &config, checkpoint);
+
+            assert_eq!(checkpoint.dial_size, 200);
+            assert_eq!(resumed, whole, "split at line {}", split);
+        }
+    }
+
+    #[test]
+    fn checkpoints_round_trip_through_text() {
+        let state = SolverState {
+            position: 32,
+            zero_count: 6,
+            rotations_applied: 10,
+            dial_size: 100,
+        };
+
+        assert_eq!(
+            state.to_string(),
+            "position=32 zero_count=6 rotations_applied=10 dial_size=100"
+        );
+        assert_eq!(state.to_string().parse(), Ok(state));
+        assert!("position=32 zero_count=6".parse::<SolverState>().is_err());
+        assert!(
+            "position=32 zero_count=6 rotations_applied=10"
+                .parse::<SolverState>()
+                .is_err()
+        );
+        assert!(
+            "position=x zero_count=6 rotations_applied=1 dial_size=100"
+                .parse::<SolverState>()
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,404 @@
+use day_1::puzzle_engine::Part;
+use day_1::report::{Base, Format};
+use std::str::FromStr;
+
+pub const DEFAULT_INPUT: &str = "input.txt";
+pub const STDIN_INPUT: &str = "-";
+pub const INPUT_ENV: &str = "AOC_INPUT";
+
+const OPTIONS: &[(&str, &str)] = &[
+    ("--part 1|2", "Puzzle part to solve (default: 2)"),
+    (
+        "--format text|json|kv",
+        "Output format for the answer (default: text)",
+    ),
+    (
+        "--answer-base 2|8|10|16",
+        "Base for the text answer (default: 10)",
+    ),
+    (
+        "--color auto|always|never",
+        "Color warnings and the answer (default: auto)",
+    ),
+    (
+        "--warn-level silent|normal|verbose",
+        "Which skipped lines to report (default: normal)",
+    ),
+    ("--start N", "Starting dial position (default: 50)"),
+    ("--degrees", "Read distances as degrees instead of clicks"),
+    ("--scale K", "Multiply every distance by K"),
+    (
+        "--output PATH",
+        "Write the answer to PATH instead of stdout",
+    ),
+    (
+        "--journal PATH",
+        "Write every applied rotation to PATH as JSON lines",
+    ),
+    ("--expect N", "Fail unless the answer equals N"),
+    ("--limit N", "Solve only the first N rotations"),
+    ("--max-clicks N", "Stop after N clicks, even mid-rotation"),
+    (
+        "--distance-range MIN:MAX",
+        "Warn about and skip distances outside MIN..=MAX",
+    ),
+    (
+        "--default-distance N",
+        "Read a bare 'L' or 'R' as turning N clicks",
+    ),
+    ("--explain", "Describe every rotation that produced a zero"),
+    (
+        "--summary",
+        "Print aggregate statistics instead of the answer",
+    ),
+    ("--fingerprint", "Print a stable hash of the answer"),
+    (
+        "--stats-json",
+        "Print the result, histogram and visited positions as JSON",
+    ),
+    (
+        "--show-dial",
+        "Draw the dial with the final position marked",
+    ),
+    ("--time", "Report how long solving took on stderr"),
+    (
+        "--strict",
+        "Fail on the first invalid line or when no rotations are found",
+    ),
+    (
+        "--ignore-unknown",
+        "Skip rotations with unknown directions silently",
+    ),
+    (
+        "--fail-on-warning",
+        "Exit with code 2 if any line failed to parse",
+    ),
+    ("--lenient", "Read invalid UTF-8 as replacement characters"),
+    (
+        "--mmap",
+        "Map each input file into memory; it must not change during the run",
+    ),
+    ("--verbose", "Print every rotation on stderr"),
+    ("--progress", "Report progress every 10% on stderr"),
+    ("--check", "Only validate the input files"),
+    (
+        "--dump-canonical",
+        "Print the input as plain rotations, one per line",
+    ),
+    ("--repl", "Apply rotations typed on stdin one by one"),
+    ("--watch", "Solve again whenever the input file changes"),
+    ("--batch", "Solve each '---'-separated puzzle on its own"),
+    ("--help", "Print this help"),
+];
+
+pub fn usage(program: &str) -> String {
+    let flags: Vec<String> = OPTIONS
+        .iter()
+        .map(|(flag, _)| format!("[{}]", flag))
+        .collect();
+
+    format!("Usage: {} {} [INPUT_FILE...]", program, flags.join(" "))
+}
+
+pub fn help(program: &str) -> String {
+    let width = OPTIONS
+        .iter()
+        .map(|(flag, _)| flag.len())
+        .max()
+        .unwrap_or(0);
+    let options: Vec<String> = OPTIONS
+        .iter()
+        .map(|(flag, description)| format!("  {:<width$}  {}", flag, description, width = width))
+        .collect();
+
+    format!(
+        "{}\n\nINPUT_FILE defaults to ${}, then stdin if piped, then {}; use {} for stdin.\n\nOptions:\n{}",
+        usage(program),
+        INPUT_ENV,
+        DEFAULT_INPUT,
+        STDIN_INPUT,
+        options.join("\n")
+    )
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            ColorChoice::Auto => is_terminal,
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+        }
+    }
+}
+
+// `normal` reports lines that failed to parse; `verbose` also notes blank and
+// comment lines. None of them change the answer.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WarnLevel {
+    Silent,
+    #[default]
+    Normal,
+    Verbose,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cli {
+    pub filenames: Vec<String>,
+    pub part: Part,
+    pub format: Format,
+    pub answer_base: Base,
+    pub color: ColorChoice,
+    pub warn_level: WarnLevel,
+    pub start: Option<i32>,
+    pub degrees: bool,
+    pub scale: Option<u32>,
+    pub output: Option<String>,
+    pub journal: Option<String>,
+    pub expect: Option<u64>,
+    pub limit: Option<usize>,
+    pub max_clicks: Option<u64>,
+    pub distance_range: Option<(i64, i64)>,
+    pub default_distance: Option<u32>,
+    pub explain: bool,
+    pub summary: bool,
+    pub show_dial: bool,
+    pub fingerprint: bool,
+    pub stats_json: bool,
+    pub time: bool,
+    pub strict: bool,
+    pub fail_on_warning: bool,
+    pub ignore_unknown: bool,
+    pub lenient: bool,
+    pub mmap: bool,
+    pub verbose: bool,
+    pub progress: bool,
+    pub check: bool,
+    pub dump_canonical: bool,
+    pub batch: bool,
+    pub repl: bool,
+    pub watch: bool,
+    pub help: bool,
+}
+
+impl Cli {
+    pub fn try_parse_from<I>(args: I) -> Result<Cli, String>
+    where
+        I: IntoIterator<Item = String>,
+    {
+        let mut cli = Cli::default();
+        let mut args = args.into_iter().skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--part" => {
+                    cli.part = match args.next().as_deref() {
+                        Some("1") => Part::One,
+                        Some("2") => Part::Two,
+                        Some(other) => return Err(format!("Invalid part '{}'", other)),
+                        None => return Err("Missing value for --part".to_string()),
+                    };
+                }
+                "--format" => {
+                    cli.format = match args.next().as_deref() {
+                        Some("text") => Format::Text,
+                        Some("json") => Format::Json,
+                        Some("kv") => Format::Kv,
+                        Some(other) => return Err(format!("Invalid format '{}'", other)),
+                        None => return Err("Missing value for --format".to_string()),
+                    };
+                }
+                "--answer-base" => {
+                    cli.answer_base = match args.next().as_deref() {
+                        Some("2") => Base::Binary,
+                        Some("8") => Base::Octal,
+                        Some("10") => Base::Decimal,
+                        Some("16") => Base::Hex,
+                        Some(other) => return Err(format!("Invalid base '{}'", other)),
+                        None => return Err("Missing value for --answer-base".to_string()),
+                    };
+                }
+                "--color" => {
+                    cli.color = match args.next().as_deref() {
+                        Some("auto") => ColorChoice::Auto,
+                        Some("always") => ColorChoice::Always,
+                        Some("never") => ColorChoice::Never,
+                        Some(other) => return Err(format!("Invalid color '{}'", other)),
+                        None => return Err("Missing value for --color".to_string()),
+                    };
+                }
+                "--warn-level" => {
+                    cli.warn_level = match args.next().as_deref() {
+                        Some("silent") => WarnLevel::Silent,
+                        Some("normal") => WarnLevel::Normal,
+                        Some("verbose") => WarnLevel::Verbose,
+                        Some(other) => return Err(format!("Invalid warning level '{}'", other)),
+                        None => return Err("Missing value for --warn-level".to_string()),
+                    };
+                }
+                "--start" => cli.start = Some(parse_value(&mut args, "--start")?),
+                "--degrees" => cli.degrees = true,
+                // A zero scale would turn every rotation into `R0`.
+                "--scale" => match parse_value(&mut args, "--scale")? {
+                    0 => return Err("Invalid value '0' for --scale".to_string()),
+                    scale => cli.scale = Some(scale),
+                },
+                "--output" => cli.output = Some(parse_value(&mut args, "--output")?),
+                "--journal" => cli.journal = Some(parse_value(&mut args, "--journal")?),
+                "--expect" => cli.expect = Some(parse_value(&mut args, "--expect")?),
+                "--limit" => cli.limit = Some(parse_value(&mut args, "--limit")?),
+                "--distance-range" => {
+                    cli.distance_range = Some(parse_range(&mut args, "--distance-range")?)
+                }
+                "--default-distance" => {
+                    cli.default_distance = Some(parse_value(&mut args, "--default-distance")?)
+                }
+                "--max-clicks" => cli.max_clicks = Some(parse_value(&mut args, "--max-clicks")?),
+                "--explain" => cli.explain = true,
+                "--summary" => cli.summary = true,
+                "--show-dial" => cli.show_dial = true,
+                "--fingerprint" => cli.fingerprint = true,
+                "--stats-json" => cli.stats_json = true,
+                "--time" => cli.time = true,
+                "--strict" => cli.strict = true,
+                "--fail-on-warning" => cli.fail_on_warning = true,
+                "--ignore-unknown" => cli.ignore_unknown = true,
+                "--lenient" => cli.lenient = true,
+                "--mmap" => cli.mmap = true,
+                "--verbose" => cli.verbose = true,
+                "--progress" => cli.progress = true,
+                "--check" => cli.check = true,
+                "--dump-canonical" => cli.dump_canonical = true,
+                "--batch" => cli.batch = true,
+                "--repl" => cli.repl = true,
+                "--watch" => cli.watch = true,
+                "--help" | "-h" => cli.help = true,
+                flag if flag.starts_with("--") => {
+                    return Err(format!("Unknown option '{}'", flag));
+                }
+                _ => cli.filenames.push(arg),
+            }
+        }
+
+        Ok(cli)
+    }
+}
+
+fn parse_value<T: FromStr>(
+    args: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<T, String> {
+    match args.next() {
+        Some(value) => value
+            .parse()
+            .map_err(|_| format!("Invalid value '{}' for {}", value, flag)),
+        None => Err(format!("Missing value for {}", flag)),
+    }
+}
+
+fn parse_range(args: &mut impl Iterator<Item = String>, flag: &str) -> Result<(i64, i64), String> {
+    let value = args
+        .next()
+        .ok_or_else(|| format!("Missing value for {}", flag))?;
+
+    match value
+        .split_once(':')
+        .map(|(min, max)| (min.parse(), max.parse()))
+    {
+        Some((Ok(min), Ok(max))) if min <= max => Ok((min, max)),
+        _ => Err(format!("Invalid range '{}' for {}", value, flag)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Cli, String> {
+        Cli::try_parse_from(
+            std::iter::once("day_1")
+                .chain(args.iter().copied())
+                .map(String::from),
+        )
+    }
+
+    #[test]
+    fn arguments_map_onto_the_config() {
+        let cli = parse(&[
+            "--part",
+            "1",
+            "--format",
+            "json",
+            "--start",
+            "7",
+            "--verbose",
+            "a.txt",
+            "b.txt",
+        ])
+        .unwrap();
+
+        assert_eq!(
+            cli,
+            Cli {
+                filenames: vec!["a.txt".to_string(), "b.txt".to_string()],
+                part: Part::One,
+                format: Format::Json,
+                start: Some(7),
+                verbose: true,
+                ..Cli::default()
+            }
+        );
+        assert_eq!(parse(&[]).unwrap(), Cli::default());
+    }
+
+    #[test]
+    fn invalid_arguments_are_rejected() {
+        assert_eq!(parse(&["--part", "3"]), Err("Invalid part '3'".to_string()));
+        assert_eq!(
+            parse(&["--warn-level", "loud"]),
+            Err("Invalid warning level 'loud'".to_string())
+        );
+        assert_eq!(
+            parse(&["--distance-range", "9:1"]),
+            Err("Invalid range '9:1' for --distance-range".to_string())
+        );
+        assert_eq!(
+            parse(&["--answer-base", "12"]),
+            Err("Invalid base '12'".to_string())
+        );
+        assert_eq!(
+            parse(&["--start", "x"]),
+            Err("Invalid value 'x' for --start".to_string())
+        );
+        assert_eq!(
+            parse(&["--scale", "0"]),
+            Err("Invalid value '0' for --scale".to_string())
+        );
+        assert_eq!(
+            parse(&["--format"]),
+            Err("Missing value for --format".to_string())
+        );
+        assert_eq!(
+            parse(&["--frobnicate"]),
+            Err("Unknown option '--frobnicate'".to_string())
+        );
+    }
+
+    #[test]
+    fn help_describes_every_option() {
+        let help = help("day_1");
+
+        assert!(help.starts_with(&usage("day_1")));
+        for (flag, description) in OPTIONS {
+            assert!(help.contains(flag) && help.contains(description));
+        }
+        assert!(parse(&["-h"]).unwrap().help);
+    }
+}
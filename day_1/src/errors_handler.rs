@@ -1,18 +1,107 @@
+use crate::puzzle_engine::{ConfigError, ParseError, SolveResult};
 use std::io::{Error, ErrorKind};
 
-pub fn handle_file_error(e: Error, filename: &str) -> ! {
+pub fn describe_file_error(e: &Error, filename: &str) -> String {
     match e.kind() {
-        ErrorKind::NotFound => {
-            eprintln!("Error: File '{}' not found", filename);
-            eprintln!("Make sure you're running from the correct directory");
-        }
-        ErrorKind::PermissionDenied => {
-            eprintln!("Error: Permission denied reading '{}'", filename);
-            eprintln!("Check file permissions");
-        }
-        _ => {
-            eprintln!("Error reading file: {}", e);
-        }
+        ErrorKind::NotFound => format!(
+            "Error: File '{}' not found\nMake sure you're running from the correct directory",
+            filename
+        ),
+        ErrorKind::PermissionDenied => format!(
+            "Error: Permission denied reading '{}'\nCheck file permissions",
+            filename
+        ),
+        ErrorKind::InvalidInput => format!(
+            "Error: Cannot read '{}': {}\nPass the input file rather than its directory",
+            filename, e
+        ),
+        ErrorKind::InvalidData => format!("Error: '{}' could not be decoded: {}", filename, e),
+        _ => format!("Error reading file: {}", e),
     }
+}
+
+pub fn handle_file_error(e: Error, filename: &str) -> ! {
+    eprintln!("{}", describe_file_error(&e, filename));
+    std::process::exit(1);
+}
+
+pub fn handle_output_error(e: Error, path: &str) -> ! {
+    eprintln!("Error: Cannot write '{}': {}", path, e);
+    std::process::exit(1);
+}
+
+pub fn handle_usage_error(message: &str, usage: &str) -> ! {
+    eprintln!("Error: {}", message);
+    eprintln!("{}", usage);
     std::process::exit(1);
 }
+
+pub fn handle_no_rotations(filename: &str) -> ! {
+    eprintln!("Error: No valid rotations found in '{}'", filename);
+    eprintln!("Check that the input is not empty (or drop --strict)");
+    std::process::exit(1);
+}
+
+pub fn handle_parse_error(filename: &str, line_number: usize, e: &ParseError) -> ! {
+    eprintln!("Error: {}:{}: {}", filename, line_number, e);
+    eprintln!("Fix the line (or drop --strict to skip it with a warning)");
+    std::process::exit(1);
+}
+
+pub const WARNING_EXIT_CODE: i32 = 2;
+
+pub fn handle_skipped_lines(skipped: usize) -> ! {
+    eprintln!("Error: {} line(s) failed to parse", skipped);
+    std::process::exit(WARNING_EXIT_CODE);
+}
+
+pub const INTERRUPT_EXIT_CODE: i32 = 130;
+
+// The count covers every rotation applied before Ctrl-C, so it is exact for
+// that prefix of the input; nothing after it was solved.
+pub fn handle_interrupt(partial: &SolveResult) -> ! {
+    eprintln!(
+        "Interrupted after {} rotations: {} zeros so far, dial at {}",
+        partial.rotations_applied, partial.zero_count, partial.final_position
+    );
+    std::process::exit(INTERRUPT_EXIT_CODE);
+}
+
+pub fn handle_config_error(e: ConfigError) -> ! {
+    eprintln!("Error: {}", e);
+    std::process::exit(1);
+}
+
+pub fn handle_unexpected_answer(expected: u64, actual: u64) -> ! {
+    eprintln!("Error: Expected {} but computed {}", expected, actual);
+    std::process::exit(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn describes_file_errors_without_exiting() {
+        let not_found = Error::from(ErrorKind::NotFound);
+        assert_eq!(
+            describe_file_error(&not_found, "input.txt"),
+            "Error: File 'input.txt' not found\nMake sure you're running from the correct directory"
+        );
+
+        let denied = Error::from(ErrorKind::PermissionDenied);
+        assert!(describe_file_error(&denied, "input.txt").contains("Permission denied"));
+
+        let directory = Error::new(ErrorKind::InvalidInput, "is a directory");
+        assert_eq!(
+            describe_file_error(&directory, "inputs"),
+            "Error: Cannot read 'inputs': is a directory\nPass the input file rather than its directory"
+        );
+
+        let other = Error::other("disk on fire");
+        assert_eq!(
+            describe_file_error(&other, "input.txt"),
+            "Error reading file: disk on fire"
+        );
+    }
+}
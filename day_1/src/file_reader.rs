@@ -1,5 +1,648 @@
-use std::fs::read_to_string;
-use std::io::Error;
+use crate::gzip;
+use crate::input_format::{INCLUDE_KEYWORD, strip_comment};
+use crate::puzzle_engine::{Direction, ParseError, parse_rotation};
+use std::fs::{File, read_to_string};
+use std::io::{Error, ErrorKind, Read};
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::thread::sleep;
+use std::time::Duration;
+
+const READ_RETRIES: u32 = 3;
+const RETRY_BACKOFF: Duration = Duration::from_millis(10);
+
+// Reading a directory fails differently on every platform, so catch it up front.
+fn reject_directory(filename: &str) -> Result<(), Error> {
+    if Path::new(filename).is_dir() {
+        return Err(Error::new(ErrorKind::InvalidInput, "is a directory"));
+    }
+
+    Ok(())
+}
+
+const BOM: char = '\u{feff}';
+
+// Some editors start UTF-8 files with a byte order mark; it is not part of the input.
+fn strip_bom(mut input: String) -> String {
+    if input.starts_with(BOM) {
+        input.drain(..BOM.len_utf8());
+    }
+    input
+}
+
+// Network filesystems occasionally fail a read that succeeds moments later.
+// Only those transient kinds are retried, with a backoff that doubles each time.
+fn retry_transient<T>(mut read: impl FnMut() -> Result<T, Error>) -> Result<T, Error> {
+    let mut backoff = RETRY_BACKOFF;
+
+    for _ in 0..READ_RETRIES {
+        match read() {
+            Err(e) if matches!(e.kind(), ErrorKind::Interrupted | ErrorKind::WouldBlock) => {
+                sleep(backoff);
+                backoff *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    read()
+}
+
 pub fn read_input_file(filename: &str) -> Result<String, Error> {
-    read_to_string(filename)
+    reject_directory(filename)?;
+    retry_transient(|| read_to_string(filename)).map(strip_bom)
+}
+
+// std cannot map files, so this declares the two libc functions it needs. The
+// mapping is private and read-only, but another process can still truncate or
+// rewrite the file under it, which is why `read_input_mmap` is unsafe.
+#[cfg(all(unix, target_pointer_width = "64"))]
+mod mapping {
+    use std::ffi::c_void;
+    use std::fs::File;
+    use std::io::Error;
+    use std::os::fd::AsRawFd;
+
+    const PROT_READ: i32 = 1;
+    const MAP_PRIVATE: i32 = 2;
+
+    unsafe extern "C" {
+        fn mmap(
+            addr: *mut c_void,
+            len: usize,
+            prot: i32,
+            flags: i32,
+            fd: i32,
+            offset: i64,
+        ) -> *mut c_void;
+        fn munmap(addr: *mut c_void, len: usize) -> i32;
+    }
+
+    #[derive(Debug)]
+    pub struct Mapping {
+        addr: *mut c_void,
+        len: usize,
+    }
+
+    impl Mapping {
+        // `len` must be the file's length, and more than zero.
+        pub fn new(file: &File, len: usize) -> Result<Self, Error> {
+            // SAFETY: a fresh read-only private mapping of an open file touches no
+            // existing memory; failure is reported through MAP_FAILED below.
+            let addr = unsafe {
+                mmap(
+                    std::ptr::null_mut(),
+                    len,
+                    PROT_READ,
+                    MAP_PRIVATE,
+                    file.as_raw_fd(),
+                    0,
+                )
+            };
+            // MAP_FAILED is all ones.
+            if addr as usize == usize::MAX {
+                return Err(Error::last_os_error());
+            }
+
+            Ok(Mapping { addr, len })
+        }
+
+        pub fn bytes(&self) -> &[u8] {
+            // SAFETY: `addr` maps `len` readable bytes until `drop`, assuming the
+            // file is not truncated or modified while mapped.
+            unsafe { std::slice::from_raw_parts(self.addr as *const u8, self.len) }
+        }
+    }
+
+    impl Drop for Mapping {
+        fn drop(&mut self) {
+            // SAFETY: `addr` and `len` came from a successful `mmap`, and no
+            // borrow of `bytes` outlives `self`.
+            unsafe {
+                munmap(self.addr, self.len);
+            }
+        }
+    }
+}
+
+// A file's text, mapped straight from the file where the platform allows and
+// read into memory otherwise.
+#[derive(Debug)]
+pub enum InputText {
+    // Checked to be UTF-8; the text starts after any byte order mark.
+    #[cfg(all(unix, target_pointer_width = "64"))]
+    Mapped(mapping::Mapping, usize),
+    Owned(String),
+}
+
+impl Deref for InputText {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        match self {
+            #[cfg(all(unix, target_pointer_width = "64"))]
+            // SAFETY: `map_input` checked the bytes are UTF-8 and `start` is a
+            // char boundary; they stay that way only if the file is not modified
+            // while mapped, which callers of `read_input_mmap` promise.
+            InputText::Mapped(mapping, start) => unsafe {
+                std::str::from_utf8_unchecked(&mapping.bytes()[*start..])
+            },
+            InputText::Owned(text) => text,
+        }
+    }
+}
+
+/// Maps a regular file into memory instead of copying it into a buffer. Empty
+/// files, pipes and `.gz` files cannot be mapped and are read by
+/// `read_input_file_auto`, as is everything on platforms without `mmap`.
+///
+/// # Safety
+///
+/// The file must not be truncated or modified, by this or any other process,
+/// until the returned `InputText` is dropped. Otherwise reading it may see
+/// invalid UTF-8 or fault with SIGBUS.
+pub unsafe fn read_input_mmap(filename: &str) -> Result<InputText, Error> {
+    reject_directory(filename)?;
+    let metadata = std::fs::metadata(filename)?;
+    let len = match usize::try_from(metadata.len()) {
+        Ok(len) if len > 0 && metadata.is_file() && !filename.ends_with(".gz") => len,
+        _ => return read_input_file_auto(filename).map(InputText::Owned),
+    };
+
+    map_input(filename, len)
+}
+
+#[cfg(all(unix, target_pointer_width = "64"))]
+fn map_input(filename: &str, len: usize) -> Result<InputText, Error> {
+    let mapping = retry_transient(|| mapping::Mapping::new(&File::open(filename)?, len))?;
+    let text =
+        std::str::from_utf8(mapping.bytes()).map_err(|e| Error::new(ErrorKind::InvalidData, e))?;
+    let start = if text.starts_with(BOM) {
+        BOM.len_utf8()
+    } else {
+        0
+    };
+
+    Ok(InputText::Mapped(mapping, start))
+}
+
+#[cfg(not(all(unix, target_pointer_width = "64")))]
+fn map_input(filename: &str, _len: usize) -> Result<InputText, Error> {
+    read_input_file_auto(filename).map(InputText::Owned)
+}
+
+// Only a mapped file that has INCLUDE lines is copied, to expand them.
+pub fn resolve_mapped_includes(input: InputText, filename: &str) -> Result<InputText, Error> {
+    if !input.lines().any(|line| include_path(line).is_some()) {
+        return Ok(input);
+    }
+
+    resolve_includes(input.to_string(), filename).map(InputText::Owned)
+}
+
+// Replaces each `INCLUDE <path>` line of `filename`'s contents with the
+// contents of `path`, resolved against the including file's directory and
+// expanded the same way. A file that includes itself, directly or not, is an
+// error. Line numbers in warnings count the expanded lines.
+pub fn resolve_includes(input: String, filename: &str) -> Result<String, Error> {
+    if !input.lines().any(|line| include_path(line).is_some()) {
+        return Ok(input);
+    }
+
+    let mut chain = vec![Path::new(filename).canonicalize()?];
+    expand_includes(&input, Path::new(filename), &mut chain)
+}
+
+fn include_path(line: &str) -> Option<&str> {
+    let (keyword, path) = strip_comment(line).trim().split_once(char::is_whitespace)?;
+    (keyword == INCLUDE_KEYWORD).then(|| path.trim())
+}
+
+// `chain` holds the canonical paths of the files currently being expanded.
+fn expand_includes(input: &str, file: &Path, chain: &mut Vec<PathBuf>) -> Result<String, Error> {
+    let mut expanded = String::with_capacity(input.len());
+
+    for line in input.lines() {
+        let Some(included) = include_path(line) else {
+            expanded.push_str(line);
+            expanded.push('\n');
+            continue;
+        };
+
+        let path = file.parent().unwrap_or(Path::new("")).join(included);
+        let cannot_include =
+            |e: Error| Error::other(format!("cannot include '{}': {}", path.display(), e));
+        let contents = read_input_file_auto(&path.to_string_lossy()).map_err(cannot_include)?;
+        let canonical = path.canonicalize().map_err(cannot_include)?;
+        if chain.contains(&canonical) {
+            return Err(Error::other(format!(
+                "include cycle: '{}' includes itself",
+                path.display()
+            )));
+        }
+
+        chain.push(canonical);
+        expanded.push_str(&expand_includes(&contents, &path, chain)?);
+        chain.pop();
+    }
+
+    Ok(expanded)
+}
+
+pub fn read_from_stdin() -> Result<String, Error> {
+    let mut input = String::new();
+    std::io::stdin().read_to_string(&mut input)?;
+    Ok(strip_bom(input))
+}
+
+pub fn read_input_file_auto(filename: &str) -> Result<String, Error> {
+    if !filename.ends_with(".gz") {
+        return read_input_file(filename);
+    }
+
+    String::from_utf8(read_bytes(filename)?)
+        .map(strip_bom)
+        .map_err(|e| Error::new(ErrorKind::InvalidData, e))
+}
+
+// Like `read_input_file_auto`, but invalid UTF-8 becomes U+FFFD instead of an
+// error, so only the affected rotations fail to parse.
+pub fn read_input_file_lenient(filename: &str) -> Result<String, Error> {
+    Ok(decode_lossy(&read_bytes(filename)?))
+}
+
+pub fn read_from_stdin_lenient() -> Result<String, Error> {
+    let mut bytes = Vec::new();
+    std::io::stdin().read_to_end(&mut bytes)?;
+    Ok(decode_lossy(&bytes))
+}
+
+fn read_bytes(filename: &str) -> Result<Vec<u8>, Error> {
+    reject_directory(filename)?;
+    let bytes = retry_transient(|| std::fs::read(filename))?;
+
+    if filename.ends_with(".gz") {
+        return gzip::decompress(&bytes);
+    }
+    Ok(bytes)
+}
+
+// One direction byte (`L` or `R`) then the distance as a little-endian u32.
+pub const BINARY_RECORD_LEN: usize = 5;
+
+pub fn read_binary_input(filename: &str) -> Result<Vec<(Direction, i64)>, Error> {
+    reject_directory(filename)?;
+    decode_binary(&retry_transient(|| std::fs::read(filename))?)
+}
+
+fn decode_binary(bytes: &[u8]) -> Result<Vec<(Direction, i64)>, Error> {
+    let trailing = bytes.len() % BINARY_RECORD_LEN;
+    if trailing > 0 {
+        return Err(Error::new(
+            ErrorKind::InvalidData,
+            format!("truncated record: {} trailing bytes", trailing),
+        ));
+    }
+
+    bytes
+        .chunks_exact(BINARY_RECORD_LEN)
+        .enumerate()
+        .map(|(index, record)| {
+            let direction = match record[0] {
+                b'L' => Direction::Left,
+                b'R' => Direction::Right,
+                other => {
+                    return Err(Error::new(
+                        ErrorKind::InvalidData,
+                        format!(
+                            "record {}: invalid direction byte 0x{:02x}",
+                            index + 1,
+                            other
+                        ),
+                    ));
+                }
+            };
+            let distance = u32::from_le_bytes([record[1], record[2], record[3], record[4]]);
+            Ok((direction, i64::from(distance)))
+        })
+        .collect()
+}
+
+type CsvRow = Result<(Direction, i64), ParseError>;
+
+// The first non-blank line is a header naming the `direction` and `distance`
+// columns, in any order and case. Every later non-blank row becomes one rotation,
+// or the error it would give as text, ready for `solve_parsed`. Fields are split
+// on commas and trimmed; quoting is not supported.
+pub fn read_csv_input(filename: &str) -> Result<Vec<CsvRow>, Error> {
+    parse_csv(&read_input_file(filename)?)
+}
+
+fn parse_csv(input: &str) -> Result<Vec<CsvRow>, Error> {
+    let mut rows = input.lines().filter(|row| !row.trim().is_empty());
+    let header: Vec<String> = rows
+        .next()
+        .unwrap_or_default()
+        .split(',')
+        .map(|name| name.trim().to_ascii_lowercase())
+        .collect();
+    let column = |name: &str| {
+        header
+            .iter()
+            .position(|column| column == name)
+            .ok_or_else(|| {
+                Error::new(
+                    ErrorKind::InvalidData,
+                    format!("CSV header has no '{}' column", name),
+                )
+            })
+    };
+    let (direction, distance) = (column("direction")?, column("distance")?);
+
+    Ok(rows
+        .map(|row| {
+            let fields: Vec<&str> = row.split(',').map(str::trim).collect();
+            match (fields.get(direction), fields.get(distance)) {
+                (Some(d), Some(n)) if d.chars().count() == 1 => {
+                    parse_rotation(&format!("{}{}", d, n))
+                }
+                (Some(d), Some(_)) if !d.is_empty() => Err(ParseError::InvalidDirection {
+                    line: row.to_string(),
+                    found: d.chars().next().unwrap_or_default(),
+                    offset: row.find(d).unwrap_or_default(),
+                }),
+                _ => Err(ParseError::TooShort {
+                    line: row.to_string(),
+                }),
+            }
+        })
+        .collect())
+}
+
+fn decode_lossy(bytes: &[u8]) -> String {
+    strip_bom(String::from_utf8_lossy(bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directories_are_rejected_as_invalid_input() {
+        let dir = std::env::temp_dir().join(format!("day_1-dir-{}.gz", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.to_str().unwrap();
+
+        for result in [read_input_file(path), read_input_file_auto(path)] {
+            let e = result.unwrap_err();
+            assert_eq!(e.kind(), ErrorKind::InvalidInput);
+            assert_eq!(e.to_string(), "is a directory");
+        }
+
+        std::fs::remove_dir(&dir).unwrap();
+    }
+
+    #[test]
+    fn leading_bom_is_stripped() {
+        let input = "L68\nL30\nR48\n";
+        let path = std::env::temp_dir().join(format!("day_1-bom-{}.txt", std::process::id()));
+        std::fs::write(
+            &path,
+            [b"\xEF\xBB\xBF".as_slice(), input.as_bytes()].concat(),
+        )
+        .unwrap();
+
+        let read = read_input_file(path.to_str().unwrap()).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(read, input);
+        let config = crate::DialConfig::default();
+        let result = crate::solve_puzzle(&read, &config);
+        assert!(result.warnings.is_empty());
+        assert_eq!(result, crate::solve_puzzle(input, &config));
+    }
+
+    #[test]
+    fn lenient_reads_turn_bad_bytes_into_warnings() {
+        let path = std::env::temp_dir().join(format!("day_1-lossy-{}.txt", std::process::id()));
+        std::fs::write(&path, b"R50\nL1\xFF0\nR20\n").unwrap();
+        let filename = path.to_str().unwrap();
+
+        let strict = read_input_file(filename);
+        let lenient = read_input_file_lenient(filename);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(strict.unwrap_err().kind(), ErrorKind::InvalidData);
+
+        let result = crate::solve_puzzle(&lenient.unwrap(), &crate::DialConfig::default());
+        assert_eq!(result.rotations_applied, 2);
+        assert_eq!(result.final_position, 20);
+        assert_eq!(result.warnings.len(), 1);
+        assert_eq!(result.warnings[0].line(), "L1\u{FFFD}0");
+    }
+
+    #[test]
+    fn mmap_reads_solve_like_normal_reads() {
+        let input: String = (0..20_000)
+            .map(|i| format!("{}{}\n", if i % 3 == 0 { 'L' } else { 'R' }, i % 997))
+            .collect();
+        let path = std::env::temp_dir().join(format!("day_1-mmap-{}.txt", std::process::id()));
+        std::fs::write(&path, &input).unwrap();
+        let filename = path.to_str().unwrap();
+
+        // SAFETY: nothing writes the file while it is mapped; removing it
+        // leaves the mapping intact.
+        let mapped = unsafe { read_input_mmap(filename) }.unwrap();
+        let normal = read_input_file(filename).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        #[cfg(all(unix, target_pointer_width = "64"))]
+        assert!(matches!(mapped, InputText::Mapped(..)));
+        assert_eq!(*mapped, normal);
+        let config = crate::DialConfig::default();
+        assert_eq!(
+            crate::solve_puzzle(&mapped, &config),
+            crate::solve_puzzle(&normal, &config)
+        );
+        assert_eq!(
+            unsafe { read_input_mmap("missing-input.txt") }
+                .unwrap_err()
+                .kind(),
+            ErrorKind::NotFound
+        );
+    }
+
+    #[test]
+    fn mapped_files_are_decoded_like_read_ones() {
+        let path = std::env::temp_dir().join(format!("day_1-mmap-bom-{}.txt", std::process::id()));
+        let filename = path.to_str().unwrap();
+
+        // SAFETY: each mapping is dropped before the file is rewritten.
+        let read = || unsafe { read_input_mmap(filename) };
+        std::fs::write(&path, "\u{feff}R5\n").unwrap();
+        assert_eq!(&*read().unwrap(), "R5\n");
+        std::fs::write(&path, "").unwrap();
+        assert_eq!(&*read().unwrap(), "");
+        std::fs::write(&path, b"R5\n\xC3(\n").unwrap();
+        let invalid = read().unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(invalid.kind(), ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn includes_are_expanded_relative_to_the_including_file() {
+        let dir = std::env::temp_dir().join(format!("day_1-include-{}", std::process::id()));
+        std::fs::create_dir_all(dir.join("parts")).unwrap();
+        std::fs::write(
+            dir.join("main.txt"),
+            "L68\nINCLUDE parts/a.txt # first half\nL82\n",
+        )
+        .unwrap();
+        std::fs::write(dir.join("parts/a.txt"), "L30\nINCLUDE b.txt\nR14").unwrap();
+        std::fs::write(dir.join("parts/b.txt"), "R48\nL5\nR60\nL55\nL1\nL99\n").unwrap();
+        std::fs::write(dir.join("loop.txt"), "R1\nINCLUDE parts/../loop.txt\n").unwrap();
+        std::fs::write(dir.join("missing.txt"), "INCLUDE nowhere.txt\n").unwrap();
+
+        let read = |name: &str| {
+            let filename = dir.join(name);
+            let filename = filename.to_str().unwrap();
+            resolve_includes(read_input_file(filename).unwrap(), filename)
+        };
+        let main = read("main.txt");
+        let cycle = read("loop.txt");
+        let missing = read("missing.txt");
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let main = main.unwrap();
+        assert_eq!(main, "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n");
+        assert_eq!(
+            crate::solve_puzzle(&main, &crate::DialConfig::default()).zero_count,
+            6
+        );
+        assert!(
+            cycle
+                .unwrap_err()
+                .to_string()
+                .starts_with("include cycle: ")
+        );
+        assert!(
+            missing
+                .unwrap_err()
+                .to_string()
+                .starts_with("cannot include ")
+        );
+        assert_eq!(
+            resolve_includes("R1\n".to_string(), "unused").unwrap(),
+            "R1\n"
+        );
+    }
+
+    #[test]
+    fn transient_errors_are_retried() {
+        let mut failures = [ErrorKind::Interrupted, ErrorKind::WouldBlock].into_iter();
+        let mut attempts = 0;
+
+        let read = retry_transient(|| {
+            attempts += 1;
+            match failures.next() {
+                Some(kind) => Err(Error::from(kind)),
+                None => Ok("R50\n".to_string()),
+            }
+        });
+
+        assert_eq!(read.unwrap(), "R50\n");
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn permanent_errors_and_exhausted_retries_are_returned() {
+        for kind in [ErrorKind::NotFound, ErrorKind::PermissionDenied] {
+            let mut attempts = 0;
+            let read: Result<(), Error> = retry_transient(|| {
+                attempts += 1;
+                Err(Error::from(kind))
+            });
+
+            assert_eq!(read.unwrap_err().kind(), kind);
+            assert_eq!(attempts, 1);
+        }
+
+        let mut attempts = 0;
+        let read: Result<(), Error> = retry_transient(|| {
+            attempts += 1;
+            Err(Error::from(ErrorKind::Interrupted))
+        });
+        assert_eq!(read.unwrap_err().kind(), ErrorKind::Interrupted);
+        assert_eq!(attempts, READ_RETRIES + 1);
+    }
+
+    #[test]
+    fn csv_rows_solve_like_text_rotations() {
+        let csv = "Direction, Distance\nL,68\n\nR, 48\nX,5\nR\nLeft,1\nr,1_0\n";
+        let path = std::env::temp_dir().join(format!("day_1-csv-{}.csv", std::process::id()));
+        std::fs::write(&path, csv).unwrap();
+
+        let rows = read_csv_input(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let config = crate::DialConfig::default();
+        let result = crate::puzzle_engine::solve_parsed(rows.unwrap(), &config);
+        let text = crate::solve_puzzle("L68\nR48\nX5\nR\nLeft1\nr1_0\n", &config);
+        assert_eq!(result.zero_count, text.zero_count);
+        assert_eq!(result.final_position, text.final_position);
+        assert_eq!(result.lines_skipped, 3);
+        assert_eq!(result.warnings[0].line(), "X5");
+        assert_eq!(result.warnings[2].line(), "Left,1");
+
+        let swapped = parse_csv("distance,direction\n5,R\n").unwrap();
+        assert_eq!(swapped, [Ok((Direction::Right, 5))]);
+        let headless = parse_csv("L,68\n").unwrap_err();
+        assert_eq!(headless.kind(), ErrorKind::InvalidData);
+        assert_eq!(headless.to_string(), "CSV header has no 'direction' column");
+    }
+
+    #[test]
+    fn binary_records_round_trip() {
+        let rotations = [
+            (Direction::Left, 68),
+            (Direction::Right, 48),
+            (Direction::Left, 4_000_000_000),
+        ];
+        let bytes: Vec<u8> = rotations
+            .iter()
+            .flat_map(|&(direction, distance)| {
+                let mut record = vec![char::from(direction) as u8];
+                record.extend((distance as u32).to_le_bytes());
+                record
+            })
+            .collect();
+        let path = std::env::temp_dir().join(format!("day_1-binary-{}.bin", std::process::id()));
+        std::fs::write(&path, &bytes).unwrap();
+
+        let read = read_binary_input(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+
+        let read = read.unwrap();
+        assert_eq!(read, rotations);
+        let config = crate::DialConfig::default();
+        assert_eq!(
+            crate::puzzle_engine::solve_parsed(read.into_iter().map(Ok), &config),
+            crate::solve_puzzle("L68\nR48\nL4000000000\n", &config)
+        );
+    }
+
+    #[test]
+    fn malformed_binary_input_is_invalid_data() {
+        let truncated = decode_binary(b"R\x05\x00\x00\x00L\x01").unwrap_err();
+        assert_eq!(truncated.kind(), ErrorKind::InvalidData);
+        assert_eq!(truncated.to_string(), "truncated record: 2 trailing bytes");
+
+        let direction = decode_binary(b"R\x05\x00\x00\x00U\x01\x00\x00\x00").unwrap_err();
+        assert_eq!(
+            direction.to_string(),
+            "record 2: invalid direction byte 0x55"
+        );
+        assert_eq!(decode_binary(b"").unwrap(), []);
+    }
 }
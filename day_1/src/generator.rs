@@ -0,0 +1,71 @@
+use std::fmt::Write;
+
+pub const MAX_DISTANCE: u64 = 999;
+
+// Small xorshift64 generator: deterministic for a given seed and good enough
+// for synthetic inputs. A zero state would stay zero forever, so it is nudged.
+#[derive(Debug, Clone)]
+pub(crate) struct XorShift(pub(crate) u64);
+
+impl XorShift {
+    pub(crate) fn new(seed: u64) -> Self {
+        XorShift(if seed == 0 {
+            0x9E37_79B9_7F4A_7C15
+        } else {
+            seed
+        })
+    }
+
+    pub(crate) fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub(crate) fn below(&mut self, bound: u64) -> u64 {
+        self.next() % bound
+    }
+}
+
+// One `L`/`R` rotation per line with distances in 1..=MAX_DISTANCE.
+pub fn generate(n: usize, seed: u64) -> String {
+    let mut rng = XorShift::new(seed);
+    let mut input = String::with_capacity(n * 5);
+
+    for _ in 0..n {
+        let direction = if rng.below(2) == 0 { 'L' } else { 'R' };
+        let distance = 1 + rng.below(MAX_DISTANCE);
+        let _ = writeln!(input, "{}{}", direction, distance);
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DialConfig, parse_rotation, solve_puzzle};
+
+    #[test]
+    fn generated_rotations_all_parse() {
+        let input = generate(10_000, 42);
+
+        assert_eq!(input.lines().count(), 10_000);
+        for line in input.lines() {
+            let (_, distance) = parse_rotation(line).unwrap();
+            assert!((1..=MAX_DISTANCE as i64).contains(&distance));
+        }
+
+        let result = solve_puzzle(&input, &DialConfig::default());
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.rotations_applied, 10_000);
+    }
+
+    #[test]
+    fn same_seed_gives_same_input() {
+        assert_eq!(generate(500, 7), generate(500, 7));
+        assert_ne!(generate(500, 7), generate(500, 8));
+        assert_eq!(generate(3, 0), generate(3, 0));
+    }
+}
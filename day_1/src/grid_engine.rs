@@ -0,0 +1,114 @@
+use crate::puzzle_engine::{COMMENT_PREFIX, ParseError, parse_distance};
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridResult {
+    pub origin_landings: u64,
+    pub final_position: (i32, i32),
+    pub moves_applied: usize,
+    pub warnings: Vec<ParseError>,
+}
+
+pub fn parse_move(line: &str) -> Result<(char, i64), ParseError> {
+    if line.len() < 2 {
+        return Err(ParseError::TooShort {
+            line: line.to_string(),
+        });
+    }
+
+    let direction = match line.chars().next().map(|d| d.to_ascii_uppercase()) {
+        Some(d @ ('U' | 'D' | 'L' | 'R')) => d,
+        found => {
+            return Err(ParseError::InvalidDirection {
+                line: line.to_string(),
+                found: found.unwrap_or_default(),
+                offset: 0,
+            });
+        }
+    };
+
+    Ok((direction, parse_distance(line)?))
+}
+
+fn wrap(coordinate: i32, offset: i64, size: u32) -> i32 {
+    let size = i64::from(size);
+    (i64::from(coordinate) + offset % size).rem_euclid(size) as i32
+}
+
+// The cursor starts at the origin (0, 0); `R`/`L` move along x, `D`/`U` along y,
+// wrapping at the grid edges. Only the cell a move ends on is checked.
+pub fn solve_grid(input: &str, width: u32, height: u32) -> GridResult {
+    let mut result = GridResult::default();
+
+    if width == 0 || height == 0 {
+        return result;
+    }
+
+    for line in input.lines() {
+        let line = line.trim();
+        if line.starts_with(COMMENT_PREFIX) {
+            continue;
+        }
+
+        for token in line.split_whitespace() {
+            let (direction, distance) = match parse_move(token) {
+                Ok(step) => step,
+                Err(e) => {
+                    result.warnings.push(e);
+                    continue;
+                }
+            };
+
+            let (x, y) = result.final_position;
+            result.final_position = match direction {
+                'R' => (wrap(x, distance, width), y),
+                'L' => (wrap(x, -distance, width), y),
+                'D' => (x, wrap(y, distance, height)),
+                _ => (x, wrap(y, -distance, height)),
+            };
+            result.moves_applied += 1;
+
+            if result.final_position == (0, 0) {
+                result.origin_landings += 1;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn moves_wrap_around_every_edge() {
+        assert_eq!(solve_grid("L1", 5, 3).final_position, (4, 0));
+        assert_eq!(solve_grid("U1", 5, 3).final_position, (0, 2));
+        assert_eq!(solve_grid("R7\nD4", 5, 3).final_position, (2, 1));
+        assert_eq!(solve_grid("R1000000000007", 5, 3).final_position, (2, 0));
+    }
+
+    #[test]
+    fn counts_landings_on_the_origin() {
+        let result = solve_grid("R5\nD3 U3\nR2 L2\n# comment\nL10\n", 5, 3);
+
+        assert_eq!(result.moves_applied, 6);
+        assert_eq!(result.origin_landings, 5);
+        assert_eq!(result.final_position, (0, 0));
+    }
+
+    #[test]
+    fn rejects_unknown_directions() {
+        let result = solve_grid("X3 U\nu2", 4, 4);
+
+        assert_eq!(result.moves_applied, 1);
+        assert_eq!(result.final_position, (0, 2));
+        assert!(matches!(
+            result.warnings.as_slice(),
+            [
+                ParseError::InvalidDirection { found: 'X', .. },
+                ParseError::TooShort { .. }
+            ]
+        ));
+    }
+}
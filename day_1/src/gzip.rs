@@ -0,0 +1,403 @@
+use std::io::{Error, ErrorKind};
+
+// A small, dependency-free gzip (RFC 1952) / DEFLATE (RFC 1951) decoder.
+// It favours clarity over speed; puzzle inputs are at most a few megabytes.
+
+const MAX_BITS: usize = 15;
+
+const LENGTH_BASE: [u16; 29] = [
+    3, 4, 5, 6, 7, 8, 9, 10, 11, 13, 15, 17, 19, 23, 27, 31, 35, 43, 51, 59, 67, 83, 99, 115, 131,
+    163, 195, 227, 258,
+];
+const LENGTH_EXTRA: [u8; 29] = [
+    0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3, 4, 4, 4, 4, 5, 5, 5, 5, 0,
+];
+const DISTANCE_BASE: [u16; 30] = [
+    1, 2, 3, 4, 5, 7, 9, 13, 17, 25, 33, 49, 65, 97, 129, 193, 257, 385, 513, 769, 1025, 1537,
+    2049, 3073, 4097, 6145, 8193, 12289, 16385, 24577,
+];
+const DISTANCE_EXTRA: [u8; 30] = [
+    0, 0, 0, 0, 1, 1, 2, 2, 3, 3, 4, 4, 5, 5, 6, 6, 7, 7, 8, 8, 9, 9, 10, 10, 11, 11, 12, 12, 13,
+    13,
+];
+const CODE_LENGTH_ORDER: [usize; 19] = [
+    16, 17, 18, 0, 8, 7, 9, 6, 10, 5, 11, 4, 12, 3, 13, 2, 14, 1, 15,
+];
+
+const FLAG_HCRC: u8 = 0x02;
+const FLAG_EXTRA: u8 = 0x04;
+const FLAG_NAME: u8 = 0x08;
+const FLAG_COMMENT: u8 = 0x10;
+
+fn invalid(message: &str) -> Error {
+    Error::new(
+        ErrorKind::InvalidData,
+        format!("corrupt gzip data: {}", message),
+    )
+}
+
+pub fn is_gzip(bytes: &[u8]) -> bool {
+    bytes.starts_with(&[0x1f, 0x8b])
+}
+
+pub fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::new();
+    let mut rest = bytes;
+
+    // A gzip file may hold several concatenated members.
+    while !rest.is_empty() {
+        let body = skip_header(rest)?;
+        let member_start = output.len();
+
+        let mut reader = BitReader::new(body);
+        inflate(&mut reader, &mut output)?;
+
+        let trailer = reader.remaining();
+        if trailer.len() < 8 {
+            return Err(invalid("truncated trailer"));
+        }
+        let expected_crc = u32::from_le_bytes([trailer[0], trailer[1], trailer[2], trailer[3]]);
+        let expected_size = u32::from_le_bytes([trailer[4], trailer[5], trailer[6], trailer[7]]);
+
+        let member = &output[member_start..];
+        if crc32(member) != expected_crc || member.len() as u32 != expected_size {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        rest = &trailer[8..];
+    }
+
+    Ok(output)
+}
+
+fn skip_header(bytes: &[u8]) -> Result<&[u8], Error> {
+    if bytes.len() < 10 || !is_gzip(bytes) {
+        return Err(invalid("missing gzip header"));
+    }
+    if bytes[2] != 8 {
+        return Err(invalid("unsupported compression method"));
+    }
+
+    let flags = bytes[3];
+    let mut rest = &bytes[10..];
+
+    if flags & FLAG_EXTRA != 0 {
+        if rest.len() < 2 {
+            return Err(invalid("truncated header"));
+        }
+        let extra_len = usize::from(u16::from_le_bytes([rest[0], rest[1]]));
+        rest = rest
+            .get(2 + extra_len..)
+            .ok_or_else(|| invalid("truncated header"))?;
+    }
+    for flag in [FLAG_NAME, FLAG_COMMENT] {
+        if flags & flag != 0 {
+            let end = rest
+                .iter()
+                .position(|&b| b == 0)
+                .ok_or_else(|| invalid("truncated header"))?;
+            rest = &rest[end + 1..];
+        }
+    }
+    if flags & FLAG_HCRC != 0 {
+        rest = rest.get(2..).ok_or_else(|| invalid("truncated header"))?;
+    }
+
+    Ok(rest)
+}
+
+struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+    buffer: u32,
+    count: u32,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            position: 0,
+            buffer: 0,
+            count: 0,
+        }
+    }
+
+    fn bits(&mut self, needed: u32) -> Result<u32, Error> {
+        while self.count < needed {
+            let byte = *self
+                .data
+                .get(self.position)
+                .ok_or_else(|| invalid("unexpected end of stream"))?;
+            self.buffer |= u32::from(byte) << self.count;
+            self.position += 1;
+            self.count += 8;
+        }
+
+        let value = self.buffer & ((1u32 << needed) - 1);
+        self.buffer >>= needed;
+        self.count -= needed;
+        Ok(value)
+    }
+
+    fn align_to_byte(&mut self) {
+        self.buffer = 0;
+        self.count = 0;
+    }
+
+    fn remaining(&self) -> &'a [u8] {
+        &self.data[self.position..]
+    }
+
+    fn take_bytes(&mut self, len: usize) -> Result<&'a [u8], Error> {
+        let bytes = self
+            .data
+            .get(self.position..self.position + len)
+            .ok_or_else(|| invalid("truncated stored block"))?;
+        self.position += len;
+        Ok(bytes)
+    }
+}
+
+struct Huffman {
+    counts: [u16; MAX_BITS + 1],
+    symbols: Vec<u16>,
+}
+
+impl Huffman {
+    fn new(lengths: &[u8]) -> Self {
+        let mut counts = [0u16; MAX_BITS + 1];
+        for &len in lengths {
+            counts[usize::from(len)] += 1;
+        }
+        counts[0] = 0;
+
+        let mut offsets = [0u16; MAX_BITS + 1];
+        for len in 1..MAX_BITS {
+            offsets[len + 1] = offsets[len] + counts[len];
+        }
+
+        let mut symbols = vec![0u16; lengths.len()];
+        for (symbol, &len) in lengths.iter().enumerate() {
+            if len != 0 {
+                symbols[usize::from(offsets[usize::from(len)])] = symbol as u16;
+                offsets[usize::from(len)] += 1;
+            }
+        }
+
+        Huffman { counts, symbols }
+    }
+
+    fn decode(&self, reader: &mut BitReader) -> Result<u16, Error> {
+        let mut code: i32 = 0;
+        let mut first: i32 = 0;
+        let mut index: i32 = 0;
+
+        for len in 1..=MAX_BITS {
+            code |= reader.bits(1)? as i32;
+            let count = i32::from(self.counts[len]);
+            if code - count < first {
+                return Ok(self.symbols[(index + (code - first)) as usize]);
+            }
+            index += count;
+            first = (first + count) << 1;
+            code <<= 1;
+        }
+
+        Err(invalid("bad huffman code"))
+    }
+}
+
+fn inflate(reader: &mut BitReader, output: &mut Vec<u8>) -> Result<(), Error> {
+    loop {
+        let last = reader.bits(1)? == 1;
+
+        match reader.bits(2)? {
+            0 => stored_block(reader, output)?,
+            1 => {
+                let (literals, distances) = fixed_tables();
+                huffman_block(reader, output, &literals, &distances)?;
+            }
+            2 => {
+                let (literals, distances) = dynamic_tables(reader)?;
+                huffman_block(reader, output, &literals, &distances)?;
+            }
+            _ => return Err(invalid("reserved block type")),
+        }
+
+        if last {
+            reader.align_to_byte();
+            return Ok(());
+        }
+    }
+}
+
+fn stored_block(reader: &mut BitReader, output: &mut Vec<u8>) -> Result<(), Error> {
+    reader.align_to_byte();
+    let header = reader.take_bytes(4)?;
+    let len = u16::from_le_bytes([header[0], header[1]]);
+    let nlen = u16::from_le_bytes([header[2], header[3]]);
+    if len != !nlen {
+        return Err(invalid("stored block length mismatch"));
+    }
+
+    output.extend_from_slice(reader.take_bytes(usize::from(len))?);
+    Ok(())
+}
+
+fn fixed_tables() -> (Huffman, Huffman) {
+    let mut lengths = [0u8; 288];
+    lengths[..144].fill(8);
+    lengths[144..256].fill(9);
+    lengths[256..280].fill(7);
+    lengths[280..].fill(8);
+
+    (Huffman::new(&lengths), Huffman::new(&[5u8; 30]))
+}
+
+fn dynamic_tables(reader: &mut BitReader) -> Result<(Huffman, Huffman), Error> {
+    let literal_count = reader.bits(5)? as usize + 257;
+    let distance_count = reader.bits(5)? as usize + 1;
+    let code_length_count = reader.bits(4)? as usize + 4;
+
+    let mut code_lengths = [0u8; 19];
+    for &index in &CODE_LENGTH_ORDER[..code_length_count] {
+        code_lengths[index] = reader.bits(3)? as u8;
+    }
+    let code_length_table = Huffman::new(&code_lengths);
+
+    let mut lengths = vec![0u8; literal_count + distance_count];
+    let mut index = 0;
+    while index < lengths.len() {
+        let symbol = code_length_table.decode(reader)?;
+        let (value, repeat) = match symbol {
+            0..=15 => (symbol as u8, 1),
+            16 => {
+                let previous = *index
+                    .checked_sub(1)
+                    .and_then(|i| lengths.get(i))
+                    .ok_or_else(|| invalid("repeat with no previous length"))?;
+                (previous, 3 + reader.bits(2)? as usize)
+            }
+            17 => (0, 3 + reader.bits(3)? as usize),
+            _ => (0, 11 + reader.bits(7)? as usize),
+        };
+
+        let end = index + repeat;
+        if end > lengths.len() {
+            return Err(invalid("too many code lengths"));
+        }
+        lengths[index..end].fill(value);
+        index = end;
+    }
+
+    let (literals, distances) = lengths.split_at(literal_count);
+    Ok((Huffman::new(literals), Huffman::new(distances)))
+}
+
+fn huffman_block(
+    reader: &mut BitReader,
+    output: &mut Vec<u8>,
+    literals: &Huffman,
+    distances: &Huffman,
+) -> Result<(), Error> {
+    loop {
+        let symbol = usize::from(literals.decode(reader)?);
+
+        match symbol {
+            0..=255 => output.push(symbol as u8),
+            256 => return Ok(()),
+            _ => {
+                let index = symbol - 257;
+                if index >= LENGTH_BASE.len() {
+                    return Err(invalid("bad length symbol"));
+                }
+                let len = usize::from(LENGTH_BASE[index])
+                    + reader.bits(u32::from(LENGTH_EXTRA[index]))? as usize;
+
+                let index = usize::from(distances.decode(reader)?);
+                if index >= DISTANCE_BASE.len() {
+                    return Err(invalid("bad distance symbol"));
+                }
+                let distance = usize::from(DISTANCE_BASE[index])
+                    + reader.bits(u32::from(DISTANCE_EXTRA[index]))? as usize;
+                if distance > output.len() {
+                    return Err(invalid("distance too far back"));
+                }
+
+                let start = output.len() - distance;
+                for offset in 0..len {
+                    output.push(output[start + offset]);
+                }
+            }
+        }
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= u32::from(byte);
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &[u8] = include_bytes!("../tests/fixtures/example.txt");
+    const LARGE: &[u8] = include_bytes!("../tests/fixtures/large.txt");
+
+    #[test]
+    fn decompresses_fixed_huffman_member_with_file_name() {
+        let compressed = include_bytes!("../tests/fixtures/example.txt.gz");
+        assert_eq!(decompress(compressed).unwrap(), EXAMPLE);
+    }
+
+    #[test]
+    fn decompresses_dynamic_huffman_member() {
+        let compressed = include_bytes!("../tests/fixtures/large.txt.gz");
+        assert_eq!(decompress(compressed).unwrap(), LARGE);
+    }
+
+    #[test]
+    fn decompresses_stored_block() {
+        let mut compressed = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 4, 3, 1, 38, 0, 217, 255];
+        compressed.extend_from_slice(EXAMPLE);
+        compressed.extend_from_slice(&crc32(EXAMPLE).to_le_bytes());
+        compressed.extend_from_slice(&(EXAMPLE.len() as u32).to_le_bytes());
+
+        assert_eq!(decompress(&compressed).unwrap(), EXAMPLE);
+    }
+
+    #[test]
+    fn decompresses_concatenated_members() {
+        let member = include_bytes!("../tests/fixtures/example.txt.gz");
+        let doubled = [&member[..], &member[..]].concat();
+
+        assert_eq!(decompress(&doubled).unwrap(), [EXAMPLE, EXAMPLE].concat());
+    }
+
+    #[test]
+    fn rejects_truncated_and_corrupt_data() {
+        let compressed = include_bytes!("../tests/fixtures/large.txt.gz");
+
+        let truncated = decompress(&compressed[..compressed.len() / 2]).unwrap_err();
+        assert_eq!(truncated.kind(), ErrorKind::InvalidData);
+
+        let mut corrupt = compressed.to_vec();
+        let last = corrupt.len() - 5;
+        corrupt[last] ^= 0xff;
+        assert_eq!(
+            decompress(&corrupt).unwrap_err().kind(),
+            ErrorKind::InvalidData
+        );
+
+        assert!(decompress(b"L68\nR20\n").is_err());
+    }
+}
@@ -0,0 +1,897 @@
+use crate::puzzle_engine::{
+    COMMENT_PREFIX, DialConfig, Direction, ParseError, parse_distance, parse_rotation,
+    unsigned_digits,
+};
+use std::io::{self, Write};
+use std::iter::Peekable;
+use std::ops::RangeInclusive;
+use std::str::SplitWhitespace;
+
+pub const REPEAT_KEYWORD: &str = "REPEAT";
+pub const RESET_KEYWORD: &str = "RESET";
+pub const SET_KEYWORD: &str = "SET";
+pub const INCLUDE_KEYWORD: &str = "INCLUDE";
+pub const DIAL_KEYWORD: &str = "DIAL";
+pub const STEP_MARKER: &str = "*";
+pub const MULTIPLIER: char = 'x';
+pub const OSCILLATION: char = 'B';
+pub const RANGE: &str = "..";
+pub const FRACTION: char = '/';
+pub const BATCH_DELIMITER: &str = "---";
+
+type Rotation = Result<(Direction, i64), ParseError>;
+type Repeated = (Rotation, u64);
+
+// One input line after comment stripping and directive recognition.
+pub(crate) enum ParsedLine<'a> {
+    Tokens(Tokens<'a>),
+    // `REPEAT <n> { ... }` must fit on a single line and cannot nest.
+    Repeat { count: u64, body: Vec<Repeated> },
+    // Snaps the dial back to its start position without clicking through anything.
+    Reset,
+    // `SET <n>` jumps straight to position `n`; it is checked against the dial when applied.
+    Set { line: &'a str, position: i64 },
+    // `DIAL <n>` swaps in an `n`-click dial, keeping the position proportional.
+    Dial { line: &'a str, size: u32 },
+    Invalid(ParseError),
+}
+
+// Lines have no size limit. Tokens are borrowed sub-slices of the line and each
+// is scanned once, so a line costs time linear in its length and allocates only
+// for tokens that fail to parse (their text goes into the error).
+// Fractions of a turn such as `R1/4` are resolved against `config` here.
+pub(crate) fn parse_line<'a>(line: &'a str, config: &DialConfig) -> ParsedLine<'a> {
+    let line = strip_comment(line).trim();
+    let units_per_turn = config.units_per_turn();
+
+    match line.split_whitespace().next() {
+        Some(REPEAT_KEYWORD) => parse_repeat(line, units_per_turn),
+        Some(RESET_KEYWORD) if line == RESET_KEYWORD => ParsedLine::Reset,
+        Some(RESET_KEYWORD) => ParsedLine::Invalid(ParseError::InvalidDirective {
+            line: line.to_string(),
+            reason: "RESET takes no arguments".to_string(),
+        }),
+        Some(SET_KEYWORD) => parse_set(line),
+        Some(DIAL_KEYWORD) => parse_dial(line),
+        // `file_reader::resolve_includes` replaces these before solving.
+        Some(INCLUDE_KEYWORD) => ParsedLine::Invalid(ParseError::InvalidDirective {
+            line: line.to_string(),
+            reason: "INCLUDE only works in input files".to_string(),
+        }),
+        _ => ParsedLine::Tokens(Tokens::new(line.split_whitespace(), units_per_turn)),
+    }
+}
+
+// Writes one `R<n>` or `L<n>` line per rotation, with REPEAT bodies, ranges,
+// multipliers, step markers and oscillations expanded and comments and invalid
+// rotations dropped. RESET, SET and DIAL lines stay, since no rotation can
+// stand in for a jump. Distances are as written, before `scale` or `degrees`,
+// so solving the output with the same config gives the same answer.
+pub fn write_canonical<W: Write>(input: &str, origin: &DialConfig, out: &mut W) -> io::Result<()> {
+    let mut config = *origin;
+
+    for line in input.lines() {
+        let parsed = parse_line(line, &config);
+        match parsed {
+            ParsedLine::Reset => writeln!(out, "{}", RESET_KEYWORD)?,
+            ParsedLine::Set { position, .. } => writeln!(out, "{} {}", SET_KEYWORD, position)?,
+            ParsedLine::Dial { .. } => {
+                if let Some(Ok(size)) = parsed.resize(&config) {
+                    config = origin.resized(size);
+                    writeln!(out, "{} {}", DIAL_KEYWORD, size)?;
+                }
+            }
+            parsed => {
+                for (direction, distance) in parsed.rotations().filter_map(Result::ok) {
+                    writeln!(out, "{}{}", direction, distance)?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// A comment starts at a `#` that begins the line or follows whitespace, so
+// `R10  # adjust` is `R10` while `R1#0` is still an invalid rotation.
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut previous = None;
+    for (index, c) in line.char_indices() {
+        if c == COMMENT_PREFIX && previous.is_none_or(char::is_whitespace) {
+            return &line[..index];
+        }
+        previous = Some(c);
+    }
+
+    line
+}
+
+fn parse_set(line: &str) -> ParsedLine<'_> {
+    let mut arguments = line[SET_KEYWORD.len()..].split_whitespace();
+
+    match (arguments.next().map(str::parse::<i64>), arguments.next()) {
+        (Some(Ok(position)), None) => ParsedLine::Set { line, position },
+        _ => ParsedLine::Invalid(ParseError::InvalidDirective {
+            line: line.to_string(),
+            reason: "SET takes a single position".to_string(),
+        }),
+    }
+}
+
+fn parse_dial(line: &str) -> ParsedLine<'_> {
+    let mut arguments = line[DIAL_KEYWORD.len()..].split_whitespace();
+
+    match (arguments.next().map(str::parse::<u32>), arguments.next()) {
+        (Some(Ok(size)), None) => ParsedLine::Dial { line, size },
+        _ => ParsedLine::Invalid(ParseError::InvalidDirective {
+            line: line.to_string(),
+            reason: "DIAL takes a single size".to_string(),
+        }),
+    }
+}
+
+fn parse_repeat(line: &str, units_per_turn: u32) -> ParsedLine<'_> {
+    let invalid = |reason: &str| {
+        ParsedLine::Invalid(ParseError::InvalidDirective {
+            line: line.to_string(),
+            reason: reason.to_string(),
+        })
+    };
+
+    let rest = line[REPEAT_KEYWORD.len()..].trim_start();
+    let (count, block) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+
+    let count = match count.parse::<u64>() {
+        Ok(count) => count,
+        Err(_) => return invalid("expected a repeat count"),
+    };
+
+    let body = match block
+        .trim()
+        .strip_prefix('{')
+        .and_then(|block| block.strip_suffix('}'))
+    {
+        Some(body) => body,
+        None => return invalid("expected a '{ ... }' block on the same line"),
+    };
+
+    if body.contains(['{', '}']) || body.split_whitespace().any(|t| t == REPEAT_KEYWORD) {
+        return invalid("nested REPEAT blocks are not supported");
+    }
+
+    ParsedLine::Repeat {
+        count,
+        body: Tokens::new(body.split_whitespace(), units_per_turn).collect(),
+    }
+}
+
+// Parses whitespace-separated rotations, folding `R * * *` into `R3`. Each item
+// carries how many times in a row the rotation applies.
+pub(crate) struct Tokens<'a> {
+    tokens: Peekable<SplitWhitespace<'a>>,
+    // The `L` half of a `B` token.
+    pending: Option<Repeated>,
+    // The distances of an `R1..5` token that are still to come.
+    range: Option<(Direction, RangeInclusive<i64>)>,
+    // What a whole turn is in the input's units, for fractions like `R1/4`.
+    units_per_turn: u32,
+}
+
+impl<'a> Tokens<'a> {
+    pub(crate) fn new(tokens: SplitWhitespace<'a>, units_per_turn: u32) -> Self {
+        Tokens {
+            tokens: tokens.peekable(),
+            pending: None,
+            range: None,
+            units_per_turn,
+        }
+    }
+}
+
+impl Iterator for Tokens<'_> {
+    type Item = Repeated;
+
+    fn next(&mut self) -> Option<Repeated> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
+        if let Some((direction, distances)) = &mut self.range {
+            if let Some(distance) = distances.next() {
+                return Some((Ok((*direction, distance)), 1));
+            }
+            self.range = None;
+        }
+
+        let token = self.tokens.next()?;
+
+        if let Some((first, _)) = token.split_once(RANGE) {
+            return match parse_range(token, first) {
+                Ok(range) => {
+                    self.range = Some(range);
+                    self.next()
+                }
+                Err(e) => Some((Err(e), 1)),
+            };
+        }
+
+        if token.len() == 1 && self.tokens.peek() == Some(&STEP_MARKER) {
+            let mut steps = 0;
+            while self.tokens.next_if_eq(&STEP_MARKER).is_some() {
+                steps += 1;
+            }
+            return Some((parse_rotation(&format!("{}{}", token, steps)), 1));
+        }
+
+        if let Some(index) = token.find(FRACTION) {
+            return Some((parse_fraction(token, index, self.units_per_turn), 1));
+        }
+
+        // `B5` swings out and back: `R5` then `L5`, ending where it started.
+        if token.len() > 1 && token.starts_with([OSCILLATION, OSCILLATION.to_ascii_lowercase()]) {
+            return Some(match parse_distance(token) {
+                Ok(distance) => {
+                    self.pending = Some((Ok((Direction::Left, distance)), 1));
+                    (Ok((Direction::Right, distance)), 1)
+                }
+                Err(e) => (Err(e), 1),
+            });
+        }
+
+        Some(parse_token(token))
+    }
+}
+
+// `R1..5` is `R1 R2 R3 R4 R5`: one rotation per distance, both ends included.
+fn parse_range(token: &str, first: &str) -> Result<(Direction, RangeInclusive<i64>), ParseError> {
+    let (direction, start) = parse_rotation(first)?;
+    let offset = first.len() + RANGE.len();
+
+    // `parse_distance` skips one leading character, here the last `.` of `..`.
+    let end = parse_distance(&token[offset - 1..]).map_err(|e| match e {
+        ParseError::InvalidNumber { source, .. } => ParseError::InvalidNumber {
+            line: token.to_string(),
+            source,
+            offset,
+        },
+        ParseError::UnexpectedSign { offset: sign, .. } => ParseError::UnexpectedSign {
+            line: token.to_string(),
+            offset: offset - 1 + sign,
+        },
+        _ => ParseError::NegativeDistance {
+            line: token.to_string(),
+            offset,
+        },
+    })?;
+
+    if end < start {
+        return Err(ParseError::DescendingRange {
+            line: token.to_string(),
+            offset,
+        });
+    }
+
+    Ok((direction, start..=end))
+}
+
+// `R1/4` is a quarter of a turn: `num * units_per_turn / den`, rounded to the
+// nearest unit with halves rounding up, so on a 100-click dial `R1/3` is 33
+// clicks and `R1/8` is 13.
+fn parse_fraction(token: &str, index: usize, units_per_turn: u32) -> Rotation {
+    let (direction, numerator) = parse_rotation(&token[..index])?;
+    let offset = index + 1;
+
+    // `parse_distance` skips one leading character, here the `/`.
+    let denominator = parse_distance(&token[index..]).map_err(|e| match e {
+        ParseError::InvalidNumber { source, .. } => ParseError::InvalidNumber {
+            line: token.to_string(),
+            source,
+            offset,
+        },
+        ParseError::UnexpectedSign { offset: sign, .. } => ParseError::UnexpectedSign {
+            line: token.to_string(),
+            offset: index + sign,
+        },
+        _ => ParseError::NegativeDistance {
+            line: token.to_string(),
+            offset,
+        },
+    })?;
+
+    if denominator == 0 {
+        return Err(ParseError::ZeroDenominator {
+            line: token.to_string(),
+            offset,
+        });
+    }
+
+    let turns = i128::from(numerator) * i128::from(units_per_turn);
+    let distance = (turns + i128::from(denominator) / 2) / i128::from(denominator);
+    Ok((direction, i64::try_from(distance).unwrap_or(i64::MAX)))
+}
+
+// `R5x4` applies `R5` four times in a row. The Part 2 count is the same as for
+// `R20`, but it stays four rotations so Part 1 can stop on the target in between.
+fn parse_token(token: &str) -> Repeated {
+    let Some((base, count)) = split_multiplier(token) else {
+        return (parse_rotation(token), 1);
+    };
+
+    let offset = base.len() + 1;
+    let count = unsigned_digits(token, count, offset).and_then(|count| {
+        count
+            .parse::<u64>()
+            .map_err(|source| ParseError::InvalidNumber {
+                line: token.to_string(),
+                source,
+                offset,
+            })
+    });
+
+    match (parse_rotation(base), count) {
+        (Ok(rotation), Ok(count)) => (Ok(rotation), count),
+        (Err(e), _) | (Ok(_), Err(e)) => (Err(e), 1),
+    }
+}
+
+// The `x` of a `0x` hex prefix right after the direction is not a multiplier.
+fn split_multiplier(token: &str) -> Option<(&str, &str)> {
+    let index = token.rfind(MULTIPLIER)?;
+    if index == 2 && token.get(1..2) == Some("0") {
+        return None;
+    }
+
+    Some((&token[..index], &token[index + 1..]))
+}
+
+impl<'a> ParsedLine<'a> {
+    // Where a RESET or SET line moves the dial, if it is one. Neither clicks
+    // through any position on the way.
+    pub(crate) fn jump(&self, config: &DialConfig) -> Option<Result<i32, ParseError>> {
+        match *self {
+            ParsedLine::Reset => Some(Ok(config.start)),
+            ParsedLine::Set { line, position } => Some(
+                i32::try_from(position)
+                    .ok()
+                    .filter(|position| (0..config.size as i32).contains(position))
+                    .ok_or_else(|| ParseError::InvalidDirective {
+                        line: line.to_string(),
+                        reason: format!("position must be between 0 and {}", config.size - 1),
+                    }),
+            ),
+            _ => None,
+        }
+    }
+
+    // The size a DIAL line switches to, if it is one. The target has to stay
+    // on the new dial.
+    pub(crate) fn resize(&self, config: &DialConfig) -> Option<Result<u32, ParseError>> {
+        let ParsedLine::Dial { line, size } = *self else {
+            return None;
+        };
+        let invalid = |reason: String| {
+            Some(Err(ParseError::InvalidDirective {
+                line: line.to_string(),
+                reason,
+            }))
+        };
+
+        if size == 0 {
+            invalid("dial size must be at least 1".to_string())
+        } else if i64::from(size) <= i64::from(config.target) {
+            invalid(format!(
+                "dial size must be above the target {}",
+                config.target
+            ))
+        } else {
+            Some(Ok(size))
+        }
+    }
+
+    pub(crate) fn rotations(self) -> LineRotations<'a> {
+        let source = match self {
+            ParsedLine::Tokens(tokens) => Source::Tokens(tokens),
+            ParsedLine::Repeat { count, body } => Source::Repeat {
+                remaining: if body.is_empty() { 0 } else { count },
+                body,
+                index: 0,
+            },
+            ParsedLine::Reset | ParsedLine::Set { .. } | ParsedLine::Dial { .. } => {
+                Source::Invalid(None)
+            }
+            ParsedLine::Invalid(e) => Source::Invalid(Some(e)),
+        };
+
+        LineRotations {
+            source,
+            pending: None,
+        }
+    }
+}
+
+// Yields every rotation of a line one at a time, expanding REPEAT blocks and
+// multipliers lazily so huge counts never allocate.
+pub(crate) struct LineRotations<'a> {
+    source: Source<'a>,
+    pending: Option<Repeated>,
+}
+
+enum Source<'a> {
+    Tokens(Tokens<'a>),
+    Repeat {
+        body: Vec<Repeated>,
+        remaining: u64,
+        index: usize,
+    },
+    Invalid(Option<ParseError>),
+}
+
+impl Source<'_> {
+    fn next(&mut self) -> Option<Repeated> {
+        match self {
+            Source::Tokens(tokens) => tokens.next(),
+            Source::Repeat {
+                body,
+                remaining,
+                index,
+            } => {
+                if *remaining == 0 {
+                    return None;
+                }
+
+                let repeated = body[*index].clone();
+                *index += 1;
+                if *index == body.len() {
+                    *index = 0;
+                    *remaining -= 1;
+                }
+                Some(repeated)
+            }
+            Source::Invalid(error) => error.take().map(|e| (Err(e), 1)),
+        }
+    }
+}
+
+impl Iterator for LineRotations<'_> {
+    type Item = Rotation;
+
+    fn next(&mut self) -> Option<Rotation> {
+        loop {
+            if let Some((rotation, count)) = &mut self.pending
+                && *count > 0
+            {
+                *count -= 1;
+                return Some(rotation.clone());
+            }
+
+            self.pending = Some(self.source.next()?);
+        }
+    }
+}
+
+// Splits a batch of puzzles on `---` lines. The delimiter lines themselves
+// belong to no segment, so N delimiters always give N + 1 segments.
+pub(crate) fn batch_segments(input: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+
+    for line in input.split_inclusive('\n') {
+        if line.trim() == BATCH_DELIMITER {
+            segments.push(&input[start..offset]);
+            start = offset + line.len();
+        }
+        offset += line.len();
+    }
+
+    segments.push(&input[start..]);
+    segments
+}
+
+pub(crate) fn line_rotations<'a>(line: &'a str, config: &DialConfig) -> LineRotations<'a> {
+    parse_line(line, config).rotations()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DialConfig, solve_puzzle};
+
+    #[test]
+    fn repeat_block_matches_unrolled_input() {
+        let config = DialConfig::default();
+
+        let repeated = solve_puzzle("R10\nREPEAT 3 { R40 L5 }\nL7\n", &config);
+        let unrolled = solve_puzzle("R10\nR40 L5\nR40 L5\nR40 L5\nL7\n", &config);
+
+        assert_eq!(repeated, unrolled);
+        assert_eq!(repeated.rotations_applied, 8);
+    }
+
+    #[test]
+    fn zero_repeat_applies_nothing() {
+        let result = solve_puzzle("REPEAT 0 { R10 }\n", &DialConfig::default());
+        assert_eq!(result.rotations_applied, 0);
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn nested_and_malformed_repeats_are_rejected() {
+        for line in [
+            "REPEAT 2 { R1 REPEAT 3 { L1 } }",
+            "REPEAT x { R1 }",
+            "REPEAT 2 R1",
+        ] {
+            let result = solve_puzzle(line, &DialConfig::default());
+            assert_eq!(result.rotations_applied, 0, "{}", line);
+            assert!(matches!(
+                result.warnings.as_slice(),
+                [ParseError::InvalidDirective { .. }]
+            ));
+        }
+    }
+
+    #[test]
+    fn invalid_rotations_inside_a_block_warn_once_per_repeat() {
+        let result = solve_puzzle("REPEAT 2 { R1 X1 }\n", &DialConfig::default());
+        assert_eq!(result.rotations_applied, 2);
+        assert_eq!(result.warnings.len(), 2);
+    }
+
+    #[test]
+    fn check_input_validates_a_repeat_body_once() {
+        let check = crate::puzzle_engine::check_input("REPEAT 5 { R1 X1 }\nREPEAT 2 { L3 }\n");
+        assert_eq!(check.valid_lines, 1);
+        assert_eq!(check.invalid_lines, 1);
+        assert_eq!(check.errors.len(), 1);
+    }
+
+    #[test]
+    fn step_markers_match_the_numeric_form() {
+        let config = DialConfig::default();
+
+        let markers = solve_puzzle("R * * *\nL * *  R4\nREPEAT 2 { l * }\n", &config);
+        let numeric = solve_puzzle("R3\nL2 R4\nREPEAT 2 { L1 }\n", &config);
+
+        assert_eq!(markers, numeric);
+        assert_eq!(markers.rotations_applied, 5);
+    }
+
+    #[test]
+    fn a_bare_direction_is_still_too_short() {
+        let result = solve_puzzle("R\n* R2", &DialConfig::default());
+
+        assert_eq!(result.rotations_applied, 1);
+        assert!(matches!(
+            result.warnings.as_slice(),
+            [ParseError::TooShort { .. }, ParseError::TooShort { .. }]
+        ));
+    }
+
+    #[test]
+    fn a_megabyte_line_parses_in_one_pass() {
+        let line = "R100 L100 ".repeat(200_000);
+        assert!(line.len() > 1_000_000);
+
+        let started = std::time::Instant::now();
+        let result = solve_puzzle(&line, &DialConfig::default());
+
+        assert_eq!(result.rotations_applied, 400_000);
+        assert_eq!(result.zero_count, 400_000);
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
+    #[test]
+    fn reset_returns_to_start_without_counting() {
+        let config = DialConfig::default();
+        let result = solve_puzzle("R20\nL5\nRESET\nR50\n", &config);
+
+        assert_eq!(result.rotations_applied, 3);
+        assert_eq!(result.final_position, 0);
+        assert_eq!(result.zero_count, 1);
+        assert_eq!(crate::final_position("R20\nRESET\nL7\n", &config), 43);
+
+        let from_zero = DialConfig { start: 0, ..config };
+        let result = solve_puzzle("R30\nRESET\nRESET\n", &from_zero);
+        assert_eq!(result.zero_count, 0);
+        assert_eq!(result.stops_on_zero, 0);
+        assert_eq!(result.final_position, 0);
+    }
+
+    #[test]
+    fn reset_with_arguments_is_rejected() {
+        let result = solve_puzzle("R20\nRESET now\n", &DialConfig::default());
+
+        assert_eq!(result.final_position, 70);
+        assert!(matches!(
+            result.warnings.as_slice(),
+            [ParseError::InvalidDirective { .. }]
+        ));
+    }
+
+    #[test]
+    fn multipliers_match_separate_rotations() {
+        let config = DialConfig::default();
+
+        for solve in [
+            crate::puzzle_engine::solve_puzzle_part1,
+            crate::puzzle_engine::solve_puzzle_part2,
+        ] {
+            assert_eq!(
+                solve("R5x4\nL50x3\nREPEAT 2 { R1x2 }\n", &config),
+                solve("R5\nR5\nR5\nR5\nL50 L50 L50\nR1 R1 R1 R1\n", &config)
+            );
+        }
+
+        let result = solve_puzzle("R0x10x2\nR7x0\n", &config);
+        assert_eq!(result.rotations_applied, 2);
+        assert_eq!(result.final_position, 82);
+    }
+
+    #[test]
+    fn malformed_multipliers_warn_once() {
+        let result = solve_puzzle("R5x\nR5xq\nQ5x1000\nR5x+3\n", &DialConfig::default());
+        assert_eq!(result.warnings[1].offset(), Some(3));
+
+        assert_eq!(result.rotations_applied, 0);
+        assert!(matches!(
+            result.warnings.as_slice(),
+            [
+                ParseError::InvalidNumber { .. },
+                ParseError::InvalidNumber { .. },
+                ParseError::InvalidDirection { .. },
+                ParseError::UnexpectedSign { offset: 3, .. }
+            ]
+        ));
+        assert!(
+            crate::puzzle_engine::check_input("R1x1000000000000000\n")
+                .errors
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn trailing_comments_are_ignored() {
+        let config = DialConfig::default();
+
+        assert_eq!(
+            solve_puzzle(
+                "R10  # adjust
+L5 R3 #twice
+   # only a comment
+",
+                &config
+            ),
+            solve_puzzle(
+                "R10
+L5 R3
+",
+                &config
+            )
+        );
+        assert_eq!(
+            solve_puzzle(
+                "REPEAT 2 { R5 } # looped
+",
+                &config
+            )
+            .rotations_applied,
+            2
+        );
+    }
+
+    #[test]
+    fn a_hash_inside_a_rotation_is_still_an_error() {
+        let result = solve_puzzle(
+            "R1#0
+R#5
+",
+            &DialConfig::default(),
+        );
+
+        assert_eq!(result.rotations_applied, 0);
+        assert_eq!(result.lines_skipped, 2);
+    }
+
+    #[test]
+    fn set_jumps_without_counting() {
+        let config = DialConfig::default();
+        let result = solve_puzzle("R20\nSET 0\nL5\nSET 99\nR1\nSET 37\n", &config);
+
+        assert_eq!(result.rotations_applied, 3);
+        assert_eq!(result.final_position, 37);
+        assert_eq!(result.zero_count, 1);
+        assert_eq!(result.stops_on_zero, 1);
+        assert_eq!(crate::final_position("R20\nSET 10\nL7\n", &config), 3);
+    }
+
+    #[test]
+    fn canonical_output_solves_the_same() {
+        let config = DialConfig::default();
+        let input = "# warm up\nREPEAT 2 { R5x2 L3 }\nR1..3 B7\nl1/4 X9\nSET 95\nR * *\nRESET\n";
+        let mut canonical = Vec::new();
+        write_canonical(input, &config, &mut canonical).unwrap();
+        let canonical = String::from_utf8(canonical).unwrap();
+
+        assert_eq!(
+            canonical,
+            "R5\nR5\nL3\nR5\nR5\nL3\nR1\nR2\nR3\nR7\nL7\nL25\nSET 95\nR2\nRESET\n"
+        );
+        assert_eq!(
+            solve_puzzle(&canonical, &config),
+            crate::SolveResult {
+                lines_skipped: 0,
+                warnings: Vec::new(),
+                ..solve_puzzle(input, &config)
+            }
+        );
+    }
+
+    #[test]
+    fn dial_resizes_and_remaps_the_position() {
+        let config = DialConfig::default();
+        let result = solve_puzzle("R49\nDIAL 50\nR10\nL60\n", &config);
+
+        // 99 of 100 becomes 49 of 50; R10 then passes zero and L60 crosses it twice.
+        assert_eq!(result.final_position, 49);
+        assert_eq!(result.zero_count, 3);
+        assert_eq!(result.dial_size, 50);
+        assert_eq!(
+            crate::final_position("R49\nDIAL 50\nR10\nL60\n", &config),
+            49
+        );
+        assert_eq!(
+            crate::final_position("R5\nDIAL 1000\nRESET\n", &config),
+            500
+        );
+        assert_eq!(
+            crate::parallel::solve_puzzle_parallel("R49\nDIAL 50\nR10\nL60\n", &config),
+            result
+        );
+    }
+
+    #[test]
+    fn invalid_dial_sizes_are_rejected() {
+        let config = DialConfig {
+            target: 5,
+            ..DialConfig::default()
+        };
+        let result = solve_puzzle("R20\nDIAL 0\nDIAL 5\nDIAL\nDIAL -4\nR1\n", &config);
+
+        assert_eq!(result.final_position, 71);
+        assert_eq!(result.lines_skipped, 4);
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "Invalid directive 'DIAL 0': dial size must be at least 1"
+        );
+        assert_eq!(
+            result.warnings[1].to_string(),
+            "Invalid directive 'DIAL 5': dial size must be above the target 5"
+        );
+    }
+
+    #[test]
+    fn set_outside_the_dial_is_rejected() {
+        let result = solve_puzzle(
+            "R20\nSET 100\nSET -1\nSET\nSET 1 2\n",
+            &DialConfig::default(),
+        );
+
+        assert_eq!(result.final_position, 70);
+        assert_eq!(result.lines_skipped, 4);
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "Invalid directive 'SET 100': position must be between 0 and 99"
+        );
+    }
+
+    #[test]
+    fn oscillations_count_both_halves_and_return() {
+        let config = DialConfig::default();
+        let result = solve_puzzle("B60\nL10 b250\n", &config);
+
+        assert_eq!(result, solve_puzzle("R60 L60\nL10 R250 L250\n", &config));
+        assert_eq!(result.zero_count, 2 + 4);
+        assert_eq!(result.final_position, 40);
+
+        let invalid = solve_puzzle("B\nBq\nB-5\n", &config);
+        assert_eq!(invalid.rotations_applied, 0);
+        assert_eq!(invalid.lines_skipped, 3);
+    }
+
+    #[test]
+    fn ranges_expand_to_increasing_distances() {
+        let config = DialConfig {
+            start: 99,
+            ..DialConfig::default()
+        };
+        let ranged = crate::solve_with_trace("R1..3\nL48..0x34 R7\n", &config);
+
+        assert_eq!(
+            ranged,
+            crate::solve_with_trace("R1 R2 R3\nL48 L49 L50 L51 L52 R7\n", &config)
+        );
+        assert_eq!(ranged.0.rotations_applied, 9);
+        assert_eq!(ranged.0.zero_count, 4);
+
+        let invalid = solve_puzzle("R5..3\nR1..x\nR2..\nR1..+5\n", &config);
+        assert_eq!(invalid.lines_skipped, 4);
+        assert_eq!(
+            invalid.warnings[0],
+            ParseError::DescendingRange {
+                line: "R5..3".to_string(),
+                offset: 4,
+            }
+        );
+        assert_eq!(invalid.warnings[1].offset(), Some(4));
+        assert_eq!(
+            invalid.warnings[3],
+            ParseError::UnexpectedSign {
+                line: "R1..+5".to_string(),
+                offset: 4,
+            }
+        );
+        assert_eq!(solve_puzzle("R4..4\n", &config).final_position, 3);
+    }
+
+    #[test]
+    fn fractions_are_parts_of_a_turn() {
+        let config = DialConfig::default();
+        let quarter = solve_puzzle("R1/4\n", &config);
+        assert_eq!((quarter.clicks, quarter.final_position), (25, 75));
+
+        // A third of 100 clicks is 33.3 and two thirds 66.7, rounded to the nearest click.
+        assert_eq!(
+            crate::solve_with_trace("R1/3 L2/3 R3/2\n", &config),
+            crate::solve_with_trace("R33 L67 R150\n", &config)
+        );
+
+        let small = DialConfig {
+            size: 7,
+            start: 0,
+            ..DialConfig::default()
+        };
+        assert_eq!(solve_puzzle("R1/2\n", &small).final_position, 4);
+        let degrees = DialConfig {
+            degrees: true,
+            ..config
+        };
+        assert_eq!(solve_puzzle("R1/4\n", &degrees).clicks, 25);
+
+        let invalid = solve_puzzle("R1/0\nR1/q\nR/4\nR1/+4\n", &config);
+        assert_eq!(invalid.lines_skipped, 4);
+        assert_eq!(
+            invalid.warnings[0],
+            ParseError::ZeroDenominator {
+                line: "R1/0".to_string(),
+                offset: 3,
+            }
+        );
+        assert_eq!(invalid.warnings[1].offset(), Some(3));
+        assert_eq!(
+            invalid.warnings[3],
+            ParseError::UnexpectedSign {
+                line: "R1/+4".to_string(),
+                offset: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn batches_split_on_delimiter_lines() {
+        assert_eq!(
+            batch_segments("R1\nL2\n---\nR3\n  ---  \n"),
+            ["R1\nL2\n", "R3\n", ""]
+        );
+        assert_eq!(batch_segments("R1\n--- x\nR2"), ["R1\n--- x\nR2"]);
+
+        let results = crate::puzzle_engine::solve_batch(
+            "L68\nL30\nR48\n---\nR50\nR100\n",
+            &DialConfig::default(),
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!((results[0].zero_count, results[0].final_position), (2, 0));
+        assert_eq!((results[1].zero_count, results[1].final_position), (2, 0));
+        assert_eq!(results[1].rotations_applied, 2);
+    }
+}
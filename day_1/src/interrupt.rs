@@ -0,0 +1,42 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// Set by the first Ctrl-C; the solver stops before its next rotation.
+pub static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+// std has no signal handling, so this declares the two libc functions it needs.
+// The handler only touches an atomic or exits, both safe inside a signal handler.
+#[cfg(unix)]
+mod unix {
+    use super::{INTERRUPTED, Ordering};
+
+    const SIGINT: i32 = 2;
+    const EXIT_CODE: i32 = 130;
+
+    unsafe extern "C" {
+        fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+        fn _exit(status: i32) -> !;
+    }
+
+    // A second Ctrl-C exits at once, in case the first arrived while reading input.
+    extern "C" fn on_interrupt(_: i32) {
+        if INTERRUPTED.swap(true, Ordering::Relaxed) {
+            unsafe { _exit(EXIT_CODE) }
+        }
+    }
+
+    pub fn install() {
+        unsafe {
+            signal(SIGINT, on_interrupt);
+        }
+    }
+}
+
+// Elsewhere Ctrl-C keeps ending the process straight away.
+pub fn install() {
+    #[cfg(unix)]
+    unix::install();
+}
+
+pub fn interrupted() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
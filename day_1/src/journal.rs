@@ -0,0 +1,176 @@
+use crate::puzzle_engine::{DialConfig, Direction, Step, walk};
+use std::fmt;
+use std::str::FromStr;
+
+// One applied rotation, written as a line of JSON, e.g.
+// `{"line": 1, "direction": "L", "distance": 68, "position": 82, "zeros": 1}`.
+// `direction` is the effective turn and `zeros` counts only this rotation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub line_number: usize,
+    pub direction: Direction,
+    pub distance: i64,
+    pub position: i32,
+    pub zeros: u64,
+}
+
+impl JournalEntry {
+    pub(crate) fn from_step(step: &Step) -> Self {
+        JournalEntry {
+            line_number: step.line_number,
+            direction: step.direction,
+            distance: step.distance,
+            position: step.to,
+            zeros: step.zeros,
+        }
+    }
+}
+
+impl fmt::Display for JournalEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{{\"line\": {}, \"direction\": \"{}\", \"distance\": {}, \"position\": {}, \"zeros\": {}}}",
+            self.line_number, self.direction, self.distance, self.position, self.zeros
+        )
+    }
+}
+
+// Reads back only what `Display` writes: the same keys in the same order.
+impl FromStr for JournalEntry {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let invalid = || format!("Invalid journal entry '{}'", s);
+        let body = s
+            .trim()
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+            .ok_or_else(invalid)?;
+
+        let mut values = body.split(',').map(|field| {
+            field
+                .split_once(':')
+                .map(|(key, value)| (key.trim().trim_matches('"'), value.trim()))
+        });
+        let mut field = |name: &str| match values.next() {
+            Some(Some((key, value))) if key == name => Ok(value),
+            _ => Err(invalid()),
+        };
+
+        let line_number = field("line")?.parse().map_err(|_| invalid())?;
+        let direction = match field("direction")?.trim_matches('"') {
+            "L" => Direction::Left,
+            "R" => Direction::Right,
+            _ => return Err(invalid()),
+        };
+        let distance = field("distance")?.parse().map_err(|_| invalid())?;
+        let position = field("position")?.parse().map_err(|_| invalid())?;
+        let zeros = field("zeros")?.parse().map_err(|_| invalid())?;
+
+        if values.next().is_some() {
+            return Err(invalid());
+        }
+
+        Ok(JournalEntry {
+            line_number,
+            direction,
+            distance,
+            position,
+            zeros,
+        })
+    }
+}
+
+pub fn to_ndjson(entries: &[JournalEntry]) -> String {
+    entries.iter().map(|entry| format!("{}\n", entry)).collect()
+}
+
+// Solves `input` again and checks it against a journal written by a Part 2
+// run of the same dial with no limits. Reports the first step that differs.
+pub fn verify_journal(input: &str, config: &DialConfig, journal_path: &str) -> Result<(), String> {
+    let journal = std::fs::read_to_string(journal_path)
+        .map_err(|e| format!("Cannot read journal '{}': {}", journal_path, e))?;
+    let recorded = journal
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(str::parse)
+        .collect::<Result<Vec<JournalEntry>, String>>()?;
+
+    let mut replayed = Vec::new();
+    walk(input, config, |step: &Step| {
+        replayed.push(JournalEntry::from_step(step))
+    });
+
+    let describe =
+        |entry: Option<&JournalEntry>| entry.map_or("nothing".to_string(), |e| e.to_string());
+    for step in 0..recorded.len().max(replayed.len()) {
+        let (expected, actual) = (recorded.get(step), replayed.get(step));
+        if expected != actual {
+            return Err(format!(
+                "Step {} differs: journal has {}, run gives {}",
+                step + 1,
+                describe(expected),
+                describe(actual)
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SolverBuilder;
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn journals_round_trip_and_catch_changes() {
+        let solver = SolverBuilder::new().build().unwrap();
+        let (result, entries) = solver.run_recorded(EXAMPLE);
+        assert_eq!(entries.len(), result.rotations_applied);
+        assert_eq!(
+            entries[0].to_string(),
+            r#"{"line": 1, "direction": "L", "distance": 68, "position": 82, "zeros": 1}"#
+        );
+        assert_eq!(entries.iter().map(|e| e.zeros).sum::<u64>(), 6);
+
+        let path =
+            std::env::temp_dir().join(format!("day_1-journal-{}.ndjson", std::process::id()));
+        let filename = path.to_str().unwrap();
+        std::fs::write(&path, to_ndjson(&entries)).unwrap();
+        let config = DialConfig::default();
+
+        assert_eq!(verify_journal(EXAMPLE, &config, filename), Ok(()));
+        assert_eq!(
+            verify_journal(&EXAMPLE.replacen("L30", "L31", 1), &config, filename),
+            Err(
+                "Step 2 differs: journal has {\"line\": 2, \"direction\": \"L\", \"distance\": 30, \
+                 \"position\": 52, \"zeros\": 0}, run gives {\"line\": 2, \"direction\": \"L\", \
+                 \"distance\": 31, \"position\": 51, \"zeros\": 0}"
+                    .to_string()
+            )
+        );
+        assert!(
+            verify_journal("L68\n", &config, filename)
+                .unwrap_err()
+                .ends_with("run gives nothing")
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn malformed_entries_are_rejected() {
+        for line in [
+            r#"{"line": 1, "direction": "U", "distance": 5, "position": 0, "zeros": 0}"#,
+            r#"{"line": 1, "distance": 5, "direction": "L", "position": 0, "zeros": 0}"#,
+            r#"{"line": 1, "direction": "L", "distance": 5, "position": 0}"#,
+            "line=1",
+        ] {
+            assert!(line.parse::<JournalEntry>().is_err(), "{}", line);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+pub mod analysis;
+pub mod checkpoint;
+pub mod errors_handler;
+pub mod file_reader;
+pub mod generator;
+pub mod grid_engine;
+pub mod gzip;
+pub mod input_format;
+pub mod journal;
+pub mod parallel;
+pub mod puzzle_engine;
+pub mod report;
+pub mod rotation_cache;
+pub mod solver;
+
+pub use analysis::{
+    TravelStats, detect_cycle, displacement, first_revisit, first_visit_indices,
+    longest_zero_streak, max_zero_rotation, noop_rotations, position_histogram, position_timeline,
+    prefix_zero_counts, returns_to_start, travel_stats, unvisited_positions, visited_positions,
+    zeros_by_line, zeros_in_window,
+};
+pub use file_reader::{read_from_stdin, read_input_file, read_input_file_auto};
+pub use puzzle_engine::{
+    DialConfig, Direction, ParseError, SolveResult, final_position, first_zero_line,
+    parse_rotation, rotations, solve_from_reader, solve_puzzle, solve_rotations, solve_with_trace,
+    verify,
+};
+pub use solver::{Solver, SolverBuilder};
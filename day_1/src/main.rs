@@ -1,17 +1,383 @@
-mod errors_handler;
-mod file_reader;
-mod puzzle_engine;
+mod cli;
+mod interrupt;
+mod repl;
+mod watch;
 
-fn main() {
-    let filename = "input.txt";
+use cli::{Cli, DEFAULT_INPUT, INPUT_ENV, STDIN_INPUT, WarnLevel};
+use day_1::file_reader::InputText;
+use day_1::journal::{self, JournalEntry};
+use day_1::puzzle_engine::{self, ParseError, SkipReason};
+use day_1::{SolveResult, Solver, SolverBuilder, analysis, errors_handler, file_reader, report};
+use report::{Format, GREEN, RED};
+use std::collections::BTreeSet;
+use std::io::{BufWriter, IsTerminal, Write};
+use std::time::{Duration, Instant};
+
+fn read_input(filename: &str, lenient: bool) -> String {
+    let result = match (filename == STDIN_INPUT, lenient) {
+        (true, false) => file_reader::read_from_stdin(),
+        (true, true) => file_reader::read_from_stdin_lenient(),
+        (false, false) => file_reader::read_input_file_auto(filename),
+        (false, true) => file_reader::read_input_file_lenient(filename),
+    };
+
+    let result = match filename {
+        STDIN_INPUT => result,
+        _ => result.and_then(|input| file_reader::resolve_includes(input, filename)),
+    };
 
-    let input = match file_reader::read_input_file(filename) {
+    match result {
         Ok(input) => input,
         Err(e) => {
             errors_handler::handle_file_error(e, filename);
         }
+    }
+}
+
+// Verbose runs note every blank and comment line before solving.
+fn note_skipped_lines(input: &str, level: WarnLevel) {
+    if level != WarnLevel::Verbose {
+        return;
+    }
+
+    for (line_number, reason) in puzzle_engine::skip_reasons(input) {
+        match reason {
+            SkipReason::Blank => eprintln!("line {}: skipped blank line", line_number),
+            SkipReason::Comment => eprintln!("line {}: skipped comment", line_number),
+            SkipReason::Invalid(_) => {}
+        }
+    }
+}
+
+fn report_warnings(warnings: &[ParseError], level: WarnLevel, color: bool) {
+    if level == WarnLevel::Silent {
+        return;
+    }
+
+    for line in puzzle_engine::collapse_warnings(warnings) {
+        if color {
+            eprintln!("{}", report::paint(&line, RED));
+        } else {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+fn build(builder: SolverBuilder) -> Solver {
+    match builder.build() {
+        Ok(solver) => solver,
+        Err(e) => errors_handler::handle_config_error(e),
+    }
+}
+
+fn check(filenames: &[String], lenient: bool) -> ! {
+    let mut valid_lines = 0;
+    let mut invalid_lines = 0;
+
+    for filename in filenames {
+        let check = puzzle_engine::check_input(&read_input(filename, lenient));
+
+        for (line_number, error) in &check.errors {
+            println!(
+                "{}:{}: Invalid rotation '{}': {}",
+                filename,
+                line_number,
+                error.line(),
+                error
+            );
+        }
+
+        valid_lines += check.valid_lines;
+        invalid_lines += check.invalid_lines;
+    }
+
+    println!(
+        "Checked {} lines: {} valid, {} invalid",
+        valid_lines + invalid_lines,
+        valid_lines,
+        invalid_lines
+    );
+    std::process::exit(if invalid_lines == 0 { 0 } else { 1 });
+}
+
+// The files' rotations in order, so solving the output carries the dial across
+// them just as solving the files would.
+fn dump_canonical(solver: &Solver, filenames: &[String], lenient: bool) -> ! {
+    let mut out = BufWriter::new(std::io::stdout().lock());
+
+    for filename in filenames {
+        let input = read_input(filename, lenient);
+        if let Err(e) = day_1::input_format::write_canonical(&input, solver.config(), &mut out) {
+            errors_handler::handle_output_error(e, STDIN_INPUT);
+        }
+    }
+    if let Err(e) = out.flush() {
+        errors_handler::handle_output_error(e, STDIN_INPUT);
+    }
+
+    std::process::exit(0);
+}
+
+// Every puzzle starts from the configured position, even across files.
+fn batch(solver: &Solver, filenames: &[String], lenient: bool, level: WarnLevel) -> ! {
+    let mut puzzle = 0;
+
+    for filename in filenames {
+        let input = read_input(filename, lenient);
+        note_skipped_lines(&input, level);
+        for result in solver.run_batch(&input) {
+            puzzle += 1;
+            report_warnings(&result.warnings, level, false);
+            println!("Puzzle {}: {}", puzzle, result.zero_count);
+        }
+    }
+
+    std::process::exit(0);
+}
+
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let program = args.first().map_or("day_1", String::as_str).to_string();
+
+    let cli = match Cli::try_parse_from(args) {
+        Ok(cli) => cli,
+        Err(message) => errors_handler::handle_usage_error(&message, &cli::usage(&program)),
+    };
+
+    // An explicit AOC_INPUT wins over piped stdin, which CI runners often leave attached.
+    let env_input = std::env::var(INPUT_ENV)
+        .ok()
+        .filter(|path| !path.is_empty());
+    if cli.help {
+        println!("{}", cli::help(&program));
+        return;
+    }
+
+    let filenames = match cli.filenames.as_slice() {
+        [] if let Some(path) = env_input => vec![path],
+        [] if !std::io::stdin().is_terminal() => vec![STDIN_INPUT.to_string()],
+        [] => vec![DEFAULT_INPUT.to_string()],
+        filenames => filenames.to_vec(),
+    };
+
+    if cli.check {
+        check(&filenames, cli.lenient);
+    }
+
+    let mut builder = SolverBuilder::new()
+        .part(cli.part)
+        .verbose(cli.verbose)
+        .progress(cli.progress)
+        .ignore_unknown(cli.ignore_unknown)
+        .degrees(cli.degrees);
+    if let Some(start) = cli.start {
+        builder = builder.start(start);
+    }
+    if let Some(scale) = cli.scale {
+        builder = builder.scale(scale);
+    }
+    if let Some(limit) = cli.limit {
+        builder = builder.limit(limit);
+    }
+    if let Some(max_clicks) = cli.max_clicks {
+        builder = builder.max_clicks(max_clicks);
+    }
+    if let Some((min, max)) = cli.distance_range {
+        builder = builder.distance_range(min, max);
+    }
+    if let Some(distance) = cli.default_distance {
+        builder = builder.default_distance(distance);
+    }
+    let mut solver = build(builder);
+
+    if cli.repl {
+        let stdin = std::io::stdin().lock();
+        if let Err(e) = repl::repl(builder, &cli, stdin, std::io::stdout().lock()) {
+            errors_handler::handle_file_error(e, STDIN_INPUT);
+        }
+        return;
+    }
+
+    if cli.dump_canonical {
+        dump_canonical(&solver, &filenames, cli.lenient);
+    }
+
+    if cli.batch {
+        batch(&solver, &filenames, cli.lenient, cli.warn_level);
+    }
+
+    if cli.watch {
+        match filenames.as_slice() {
+            [filename] if filename != STDIN_INPUT => watch::watch(
+                &solver,
+                filename,
+                cli.format,
+                cli.answer_base,
+                cli.warn_level,
+            ),
+            _ => errors_handler::handle_usage_error(
+                "--watch needs a single input file",
+                &cli::usage(&program),
+            ),
+        }
+    }
+
+    if cli.journal.is_some() && (cli.explain || cli.strict) {
+        errors_handler::handle_usage_error(
+            "--journal cannot be combined with --explain or --strict",
+            &cli::usage(&program),
+        );
+    }
+
+    if cli.explain && cli.strict {
+        errors_handler::handle_usage_error(
+            "--explain cannot be combined with --strict",
+            &cli::usage(&program),
+        );
+    }
+
+    // `auto` decides per stream, so piping stdout still colors terminal warnings.
+    let color_stdout = cli.color.enabled(std::io::stdout().is_terminal());
+    let color_stderr = cli.color.enabled(std::io::stderr().is_terminal());
+
+    let mut total = SolveResult {
+        final_position: solver.config().start,
+        dial_size: solver.config().size,
+        ..SolveResult::default()
     };
 
-    let password = puzzle_engine::solve_puzzle(&input);
-    println!("Password: {}", password);
+    // Ctrl-C from here on stops the solve and reports what it has so far.
+    interrupt::install();
+    builder = builder.interrupt(&interrupt::INTERRUPTED);
+    solver = build(builder);
+
+    // Only the solve itself is timed, not reading the input.
+    let mut solve_time = Duration::ZERO;
+
+    // Filled in only for --stats-json, which walks every file a second time.
+    let mut histogram: Vec<u64> = Vec::new();
+    let mut visited = BTreeSet::new();
+    let mut entries: Vec<JournalEntry> = Vec::new();
+
+    for filename in &filenames {
+        let input = if cli.mmap && !cli.lenient && filename != STDIN_INPUT {
+            // SAFETY: --mmap documents that the input must not change while the
+            // run reads it; each mapping is dropped before the next file.
+            unsafe { file_reader::read_input_mmap(filename) }
+                .and_then(|input| file_reader::resolve_mapped_includes(input, filename))
+                .unwrap_or_else(|e| errors_handler::handle_file_error(e, filename))
+        } else {
+            InputText::Owned(read_input(filename, cli.lenient))
+        };
+        note_skipped_lines(&input, cli.warn_level);
+        let started = Instant::now();
+        let (result, explanations) = if cli.explain {
+            solver.run_explained(&input)
+        } else if cli.strict {
+            match solver.run_strict(&input) {
+                Ok(result) => (result, Vec::new()),
+                Err((line_number, e)) => {
+                    errors_handler::handle_parse_error(filename, line_number, &e)
+                }
+            }
+        } else if cli.journal.is_some() {
+            let (result, recorded) = solver.run_recorded(&input);
+            entries.extend(recorded);
+            (result, Vec::new())
+        } else {
+            (solver.run(&input), Vec::new())
+        };
+        solve_time += started.elapsed();
+
+        if cli.stats_json {
+            let counts = analysis::position_histogram(&input, &solver.starting_dial());
+            if histogram.is_empty() {
+                histogram = counts;
+            } else {
+                for (total, count) in histogram.iter_mut().zip(counts) {
+                    *total += count;
+                }
+            }
+            visited.extend(analysis::visited_positions(&input, &solver.starting_dial()));
+        }
+
+        for explanation in &explanations {
+            println!("{}", explanation);
+        }
+
+        report_warnings(&result.warnings, cli.warn_level, color_stderr);
+
+        if filenames.len() > 1 {
+            eprintln!("Subtotal for '{}': {}", filename, result.zero_count);
+        }
+
+        // The dial keeps its size and position from one file to the next, and
+        // the limits span all files.
+        total.merge(result);
+        if let Some(limit) = cli.limit {
+            builder = builder.limit(limit.saturating_sub(total.rotations_applied));
+        }
+        if let Some(max_clicks) = cli.max_clicks {
+            builder = builder.max_clicks(max_clicks.saturating_sub(total.clicks));
+        }
+        solver = build(builder).continue_from(&total);
+
+        if interrupt::interrupted() {
+            errors_handler::handle_interrupt(&total);
+        }
+    }
+
+    // Line numbers in the journal restart with every file.
+    if let Some(path) = &cli.journal
+        && let Err(e) = std::fs::write(path, journal::to_ndjson(&entries))
+    {
+        errors_handler::handle_output_error(e, path);
+    }
+
+    if cli.time {
+        eprintln!("solved in {:.3} ms", solve_time.as_secs_f64() * 1000.0);
+    }
+
+    if cli.strict && total.rotations_applied == 0 {
+        errors_handler::handle_no_rotations(&filenames.join(", "));
+    }
+
+    let answer = if cli.stats_json {
+        report::stats_json(&total, &histogram, visited.len())
+    } else if cli.summary {
+        report::render_summary(&total)
+    } else {
+        report::render(&total, cli.format, cli.answer_base)
+    };
+
+    if let Some(path) = &cli.output {
+        if let Err(e) = std::fs::write(path, answer + "\n") {
+            errors_handler::handle_output_error(e, path);
+        }
+    } else if !cli.summary && !cli.stats_json && cli.format == Format::Text && color_stdout {
+        println!("{}", report::paint(&answer, GREEN));
+    } else {
+        println!("{}", answer);
+    }
+
+    if cli.show_dial {
+        println!(
+            "{}",
+            report::render_dial(total.final_position, total.dial_size)
+        );
+    }
+
+    if cli.fingerprint {
+        println!("Fingerprint: {:016x}", report::run_fingerprint(&total));
+    }
+
+    if let Some(expected) = cli.expect
+        && expected != total.zero_count
+    {
+        errors_handler::handle_unexpected_answer(expected, total.zero_count);
+    }
+
+    if cli.fail_on_warning && total.lines_skipped > 0 {
+        errors_handler::handle_skipped_lines(total.lines_skipped);
+    }
 }
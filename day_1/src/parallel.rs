@@ -0,0 +1,161 @@
+use crate::input_format::parse_line;
+use crate::puzzle_engine::{
+    DialConfig, Direction, SolveResult, apply_rotation_with_zero_count, full_revolutions,
+    remap_position, signed_distance, stop_count,
+};
+use std::thread;
+
+// Below this many rotations the threads cost more than they save.
+const MIN_CHUNK: usize = 4_096;
+
+// Where the dial lands is cheap to compute on its own, so a sequential scan
+// first records the start of every rotation. The crossings of each rotation
+// depend only on that start and the dial's size, so they are counted on
+// several threads at once.
+pub fn solve_puzzle_parallel(input: &str, origin: &DialConfig) -> SolveResult {
+    let mut config = *origin;
+    let mut result = SolveResult::initial(&config);
+    let mut steps = Vec::new();
+
+    for line in input.lines() {
+        let parsed = parse_line(line, &config);
+        match parsed.resize(&config) {
+            Some(Ok(size)) => {
+                result.final_position = remap_position(result.final_position, config.size, size);
+                result.dial_size = size;
+                config = origin.resized(size);
+                continue;
+            }
+            Some(Err(e)) => {
+                result.lines_skipped += 1;
+                result.warnings.push(e);
+                continue;
+            }
+            None => {}
+        }
+        match parsed.jump(&config) {
+            Some(Ok(position)) => {
+                result.final_position = position;
+                continue;
+            }
+            Some(Err(e)) => {
+                result.lines_skipped += 1;
+                result.warnings.push(e);
+                continue;
+            }
+            None => {}
+        }
+
+        for rotation in parsed.rotations() {
+            match rotation {
+                Ok((_, 0)) => continue,
+                Ok((direction, distance)) => {
+                    let direction = config.turn(direction);
+                    let distance = config.clicks(distance);
+                    if distance == 0 {
+                        continue;
+                    }
+                    let from = result.final_position;
+                    let size = i64::from(config.size);
+                    let offset = direction.sign() * (distance % size);
+
+                    steps.push((from, direction, distance, config.size));
+                    result.final_position = (i64::from(from) + offset).rem_euclid(size) as i32;
+                    result.rotations_applied += 1;
+                    result.clicks = result.clicks.saturating_add(distance as u64);
+                    result.net_offset += signed_distance(direction, distance);
+                    result.full_revolutions = full_revolutions(result.net_offset, config.size);
+                    result.add_distance(direction, distance);
+                }
+                Err(e) => {
+                    result.lines_skipped += 1;
+                    result.warnings.push(e);
+                }
+            }
+        }
+    }
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = steps.len().div_ceil(threads).max(MIN_CHUNK);
+
+    let counts: Vec<(u64, u64)> = thread::scope(|scope| {
+        let handles: Vec<_> = steps
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || count_chunk(chunk, &config)))
+            .collect();
+
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("counting thread panicked"))
+            .collect()
+    });
+
+    for (passes, stops) in counts {
+        result.passes_through_zero = result.passes_through_zero.saturating_add(passes);
+        result.stops_on_zero += stops;
+    }
+    result.zero_count = result.passes_through_zero;
+    result.update_zero_fraction();
+
+    result
+}
+
+fn count_chunk(chunk: &[(i32, Direction, i64, u32)], config: &DialConfig) -> (u64, u64) {
+    let mut passes = 0u64;
+    let mut stops = 0;
+
+    for &(from, direction, distance, size) in chunk {
+        let config = DialConfig { size, ..*config };
+        let (to, hits) = apply_rotation_with_zero_count(from, direction, distance, &config);
+        passes = passes.saturating_add(hits);
+        stops += stop_count(to, &config);
+    }
+
+    (passes, stops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::generate;
+    use crate::solve_puzzle;
+
+    #[test]
+    fn parallel_matches_serial_on_a_large_input() {
+        let input =
+            generate(200_000, 2025) + "X1\nR0\nRESET\nL\nSET 3\nSET 900\n" + &generate(50_000, 7);
+
+        for config in [
+            DialConfig::default(),
+            DialConfig {
+                size: 7,
+                start: 3,
+                target: 5,
+                clockwise_is_right: false,
+                ..DialConfig::default()
+            },
+            DialConfig {
+                start: 0,
+                degrees: true,
+                count_initial: true,
+                ..DialConfig::default()
+            },
+        ] {
+            assert_eq!(
+                solve_puzzle_parallel(&input, &config),
+                solve_puzzle(&input, &config)
+            );
+        }
+    }
+
+    #[test]
+    fn parallel_handles_tiny_and_empty_inputs() {
+        let config = DialConfig::default();
+        for input in ["", "R50\n", "L68\nL30\nR48\n"] {
+            assert_eq!(
+                solve_puzzle_parallel(input, &config),
+                solve_puzzle(input, &config)
+            );
+        }
+    }
+}
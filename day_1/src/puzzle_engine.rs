@@ -1,112 +1,2930 @@
-fn parse_rotation(line: &str) -> Result<(char, i32), String> {
+use crate::input_format::{ParsedLine, batch_segments, line_rotations, parse_line};
+use crate::rotation_cache::RotationCache;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::num::ParseIntError;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+pub const COMMENT_PREFIX: char = '#';
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DialConfig {
+    pub size: u32,
+    pub start: i32,
+    pub target: i32,
+    // When false, `L` increases the position and `R` decreases it.
+    pub clockwise_is_right: bool,
+    // When true, distances are degrees (360 per turn) rather than clicks.
+    pub degrees: bool,
+    // Every written distance is multiplied by this before it is applied.
+    pub scale: u32,
+    // When true, starting on the target counts as one zero before any rotation.
+    pub count_initial: bool,
+    // When true, the dial is numbered the other way round: position `p` is
+    // `(size - p) % size` on the unmirrored dial. Every position given or
+    // reported (start, target, `SET`, results) uses the mirrored numbering.
+    pub mirrored: bool,
+}
+
+impl Default for DialConfig {
+    fn default() -> Self {
+        DialConfig {
+            size: 100,
+            start: 50,
+            target: 0,
+            clockwise_is_right: true,
+            degrees: false,
+            scale: 1,
+            count_initial: false,
+            mirrored: false,
+        }
+    }
+}
+
+impl DialConfig {
+    // Maps a written direction onto the solver's convention, where `R` increases the position.
+    // Mirroring the numbering reverses which way each turn moves through it.
+    pub fn turn(&self, direction: Direction) -> Direction {
+        if self.clockwise_is_right == self.mirrored {
+            direction.reversed()
+        } else {
+            direction
+        }
+    }
+
+    // An empty dial has no positions to reflect.
+    pub fn mirror(&self, position: i32) -> Result<i32, ConfigError> {
+        if self.size == 0 {
+            return Err(ConfigError::EmptyDial);
+        }
+        let size = i64::from(self.size);
+        Ok((size - i64::from(position)).rem_euclid(size) as i32)
+    }
+
+    // How many clicks a written distance turns, after multiplying by `scale`.
+    // Degrees are scaled by `size / 360` and rounded to the nearest click,
+    // halves rounding up, so on a 100-click dial `R90` is 25 clicks and `R1`
+    // (0.28 clicks) is none. Distances too long for an i64 saturate.
+    pub fn clicks(&self, distance: i64) -> i64 {
+        let scaled = i128::from(distance) * i128::from(self.scale);
+        let clicks = if self.degrees {
+            (scaled * i128::from(self.size) + 180) / 360
+        } else {
+            scaled
+        };
+        i64::try_from(clicks).unwrap_or(i64::MAX)
+    }
+
+    // The `size`-click dial a DIAL line switches to. The start is remapped
+    // from this config rather than from the last DIAL, so RESET lands in the
+    // same place whichever dials came before.
+    pub(crate) fn resized(&self, size: u32) -> DialConfig {
+        DialConfig {
+            size,
+            start: remap_position(self.start, self.size, size),
+            ..*self
+        }
+    }
+
+    // A whole turn in the units distances are written in.
+    pub fn units_per_turn(&self) -> u32 {
+        if self.degrees { 360 } else { self.size }
+    }
+
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.size == 0 {
+            return Err(ConfigError::EmptyDial);
+        }
+        if !(0..self.size as i32).contains(&self.start) {
+            return Err(ConfigError::StartOutOfRange {
+                start: self.start,
+                size: self.size,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Left,
+    Right,
+}
+
+impl Direction {
+    // How a turn moves the position: up by one per click for `Right`.
+    pub fn sign(self) -> i64 {
+        match self {
+            Direction::Left => -1,
+            Direction::Right => 1,
+        }
+    }
+
+    // The opposite turn, which undoes this one.
+    pub fn reversed(self) -> Direction {
+        match self {
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+        }
+    }
+}
+
+// Either case is accepted, as in the input; the error is the rejected char.
+impl TryFrom<char> for Direction {
+    type Error = char;
+
+    fn try_from(c: char) -> Result<Self, char> {
+        match c {
+            'L' | 'l' => Ok(Direction::Left),
+            'R' | 'r' => Ok(Direction::Right),
+            other => Err(other),
+        }
+    }
+}
+
+impl From<Direction> for char {
+    fn from(direction: Direction) -> char {
+        match direction {
+            Direction::Left => 'L',
+            Direction::Right => 'R',
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", char::from(*self))
+    }
+}
+
+// Where `position` on a `from`-click dial lands on a `to`-click one:
+// `position * to / from`, rounded down, so 99 of 100 becomes 49 of 50.
+pub fn remap_position(position: i32, from: u32, to: u32) -> i32 {
+    (i64::from(position) * i64::from(to) / i64::from(from)) as i32
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    EmptyDial,
+    StartOutOfRange { start: i32, size: u32 },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::EmptyDial => write!(f, "Dial size must be at least 1"),
+            ConfigError::StartOutOfRange { start, size } => write!(
+                f,
+                "Start position {} is outside the dial (expected 0..{})",
+                start, size
+            ),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Part {
+    One,
+    #[default]
+    Two,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SolveResult {
+    pub zero_count: u64,
+    pub passes_through_zero: u64,
+    pub stops_on_zero: u64,
+    pub final_position: i32,
+    pub rotations_applied: usize,
+    pub clicks: u64,
+    // `zero_count / clicks`, or 0.0 before the first click.
+    pub zero_fraction: f64,
+    // Raw distance turned each way, after `DialConfig::turn`.
+    pub left_distance: u64,
+    pub right_distance: u64,
+    // Signed travel (`R` positive) and how many whole turns of `dial_size` it spans.
+    pub net_offset: i128,
+    pub full_revolutions: u64,
+    pub dial_size: u32,
+    pub lines_skipped: usize,
+    pub warnings: Vec<ParseError>,
+}
+
+impl SolveResult {
+    // The result before any rotation has been applied.
+    pub(crate) fn initial(config: &DialConfig) -> SolveResult {
+        let zeros = u64::from(config.count_initial && config.start == config.target);
+
+        SolveResult {
+            zero_count: zeros,
+            passes_through_zero: zeros,
+            stops_on_zero: zeros,
+            final_position: config.start,
+            dial_size: config.size,
+            ..SolveResult::default()
+        }
+    }
+
+    pub(crate) fn update_zero_fraction(&mut self) {
+        self.zero_fraction = match self.clicks {
+            0 => 0.0,
+            clicks => self.zero_count as f64 / clicks as f64,
+        };
+    }
+
+    pub(crate) fn add_distance(&mut self, direction: Direction, distance: i64) {
+        let total = match direction {
+            Direction::Left => &mut self.left_distance,
+            Direction::Right => &mut self.right_distance,
+        };
+        *total = total.saturating_add(distance as u64);
+    }
+
+    // Folds in the result of solving the input that directly followed this one,
+    // as if both had been solved in a single pass.
+    pub fn merge(&mut self, next: SolveResult) {
+        self.zero_count += next.zero_count;
+        self.passes_through_zero += next.passes_through_zero;
+        self.stops_on_zero += next.stops_on_zero;
+        self.final_position = next.final_position;
+        self.rotations_applied += next.rotations_applied;
+        self.clicks = self.clicks.saturating_add(next.clicks);
+        self.left_distance = self.left_distance.saturating_add(next.left_distance);
+        self.right_distance = self.right_distance.saturating_add(next.right_distance);
+        self.net_offset += next.net_offset;
+        self.dial_size = next.dial_size;
+        self.full_revolutions = full_revolutions(self.net_offset, self.dial_size);
+        self.update_zero_fraction();
+        self.lines_skipped += next.lines_skipped;
+        self.warnings.extend(next.warnings);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    TooShort {
+        line: String,
+    },
+    // `offset` is the byte offset in `line` where parsing broke.
+    InvalidDirection {
+        line: String,
+        found: char,
+        offset: usize,
+    },
+    InvalidNumber {
+        line: String,
+        source: ParseIntError,
+        offset: usize,
+    },
+    NegativeDistance {
+        line: String,
+        offset: usize,
+    },
+    DescendingRange {
+        line: String,
+        offset: usize,
+    },
+    ZeroDenominator {
+        line: String,
+        offset: usize,
+    },
+    UnexpectedSign {
+        line: String,
+        offset: usize,
+    },
+    DistanceOutOfRange {
+        line: String,
+        min: i64,
+        max: i64,
+    },
+    InvalidDirective {
+        line: String,
+        reason: String,
+    },
+    // Not a parse failure: the rotation was applied, but its arithmetic left the
+    // dial at `position`, which was wrapped back. Only release builds get here.
+    PositionOffDial {
+        line: String,
+        position: i32,
+        size: u32,
+    },
+}
+
+impl ParseError {
+    pub fn line(&self) -> &str {
+        match self {
+            ParseError::TooShort { line }
+            | ParseError::InvalidDirection { line, .. }
+            | ParseError::InvalidNumber { line, .. }
+            | ParseError::NegativeDistance { line, .. }
+            | ParseError::DescendingRange { line, .. }
+            | ParseError::ZeroDenominator { line, .. }
+            | ParseError::UnexpectedSign { line, .. }
+            | ParseError::DistanceOutOfRange { line, .. }
+            | ParseError::InvalidDirective { line, .. }
+            | ParseError::PositionOffDial { line, .. } => line,
+        }
+    }
+
+    pub fn offset(&self) -> Option<usize> {
+        match self {
+            ParseError::InvalidDirection { offset, .. }
+            | ParseError::InvalidNumber { offset, .. }
+            | ParseError::NegativeDistance { offset, .. }
+            | ParseError::DescendingRange { offset, .. }
+            | ParseError::ZeroDenominator { offset, .. }
+            | ParseError::UnexpectedSign { offset, .. } => Some(*offset),
+            ParseError::DistanceOutOfRange { .. } => Some(1),
+            ParseError::TooShort { .. }
+            | ParseError::InvalidDirective { .. }
+            | ParseError::PositionOffDial { .. } => None,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::TooShort { line } => write!(f, "Line too short: '{}'", line),
+            ParseError::InvalidDirection { line, offset, .. } => {
+                write!(
+                    f,
+                    "Invalid direction at column {} in '{}'",
+                    offset + 1,
+                    line
+                )
+            }
+            ParseError::InvalidNumber {
+                line,
+                source,
+                offset,
+            } => write!(
+                f,
+                "Invalid number at column {} in '{}': {}",
+                offset + 1,
+                line,
+                source
+            ),
+            ParseError::NegativeDistance { line, offset } => {
+                write!(
+                    f,
+                    "Negative distance at column {} in '{}'",
+                    offset + 1,
+                    line
+                )
+            }
+            ParseError::DescendingRange { line, offset } => {
+                write!(
+                    f,
+                    "Range ends below its start at column {} in '{}'",
+                    offset + 1,
+                    line
+                )
+            }
+            ParseError::ZeroDenominator { line, offset } => {
+                write!(f, "Zero denominator at column {} in '{}'", offset + 1, line)
+            }
+            ParseError::UnexpectedSign { line, offset } => {
+                write!(f, "Unexpected sign at column {} in '{}'", offset + 1, line)
+            }
+            ParseError::DistanceOutOfRange { line, min, max } => write!(
+                f,
+                "Distance out of range in '{}': expected {} to {}",
+                line, min, max
+            ),
+            ParseError::InvalidDirective { line, reason } => {
+                write!(f, "Invalid directive '{}': {}", line, reason)
+            }
+            ParseError::PositionOffDial { position, size, .. } => write!(
+                f,
+                "Position {} left the dial 0..{}; wrapped it back",
+                position, size
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ParseError::InvalidNumber { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+pub fn parse_rotation(line: &str) -> Result<(Direction, i64), ParseError> {
     if line.len() < 2 {
-        return Err(format!("Line too short: '{}'", line));
+        return Err(ParseError::TooShort {
+            line: line.to_string(),
+        });
     }
 
+    // Signed offsets: `+n` turns clockwise like `Rn`, `-n` like `Ln`. A bare
+    // number has no direction and is rejected as an invalid direction.
     let direction = match line.chars().next() {
-        Some(d @ 'L') | Some(d @ 'R') => d,
-        _ => return Err(format!("Invalid direction in '{}'", line)),
+        Some('+') => Direction::Right,
+        Some('-') => Direction::Left,
+        Some(c) => Direction::try_from(c).map_err(|found| ParseError::InvalidDirection {
+            line: line.to_string(),
+            found,
+            offset: 0,
+        })?,
+        None => {
+            return Err(ParseError::TooShort {
+                line: line.to_string(),
+            });
+        }
+    };
+
+    Ok((direction, parse_distance(line)?))
+}
+
+// Parses everything after the one-character direction of `line`.
+// `1_000` reads as `1000`, but only when every underscore sits between two
+// digits; anything else is left untouched so it fails to parse.
+fn strip_digit_separators(digits: &str) -> std::borrow::Cow<'_, str> {
+    if !digits.contains('_') {
+        return digits.into();
+    }
+
+    let bytes = digits.as_bytes();
+    let well_placed = bytes.iter().enumerate().all(|(i, &b)| {
+        b != b'_'
+            || (i > 0
+                && bytes[i - 1].is_ascii_hexdigit()
+                && bytes.get(i + 1).is_some_and(u8::is_ascii_hexdigit))
+    });
+
+    if well_placed {
+        digits.replace('_', "").into()
+    } else {
+        digits.into()
+    }
+}
+
+// `parse` and `from_str_radix` also take a leading `+`, but distances and
+// counts are written as bare digits. `offset` is where `digits` starts in `line`.
+pub(crate) fn unsigned_digits<'a>(
+    line: &str,
+    digits: &'a str,
+    offset: usize,
+) -> Result<&'a str, ParseError> {
+    match digits.strip_prefix('+') {
+        Some(_) => Err(ParseError::UnexpectedSign {
+            line: line.to_string(),
+            offset,
+        }),
+        None => Ok(digits),
+    }
+}
+
+pub(crate) fn parse_distance(line: &str) -> Result<i64, ParseError> {
+    let digits = strip_digit_separators(&line[1..]);
+
+    // `0x` switches to hexadecimal; everything else is decimal.
+    let (digits, radix, offset) = match digits
+        .strip_prefix("0x")
+        .or_else(|| digits.strip_prefix("0X"))
+    {
+        Some(hex) => (hex, 16, 3),
+        None => (&*digits, 10, 1),
     };
+    let parsed = i64::from_str_radix(unsigned_digits(line, digits, offset)?, radix);
 
-    let distance = match line[1..].parse::<i32>() {
+    let distance = match parsed {
         Ok(d) => d,
-        Err(e) => return Err(format!("Invalid number in '{}': {}", line, e)),
+        Err(source) => {
+            return Err(ParseError::InvalidNumber {
+                line: line.to_string(),
+                source,
+                offset: 1,
+            });
+        }
     };
 
-    Ok((direction, distance))
+    if distance < 0 {
+        return Err(ParseError::NegativeDistance {
+            line: line.to_string(),
+            offset: 1,
+        });
+    }
+
+    Ok(distance)
 }
 
 // ============================================================================
 // PART 1: Count zeros only at the END of each rotation
 // ============================================================================
 
-// fn apply_rotation(position: i32, direction: char, distance: i32) -> i32 {
-//     match direction {
-//         'R' => (position + distance) % 100,
-//         'L' => (position - distance + 100) % 100,
-//         _ => position,
-//     }
-// }
-//
-// pub fn solve_puzzle(input: &str) -> u32 {
-//     let mut position = 50;
-//     let mut count = 0;
-//
-//     for line in input.lines() {
-//         let line = line.trim();
-//
-//         if line.is_empty() {
-//             continue;
-//         }
-//
-//         match parse_rotation(line) {
-//             Ok((direction, distance)) => {
-//                 position = apply_rotation(position, direction, distance);
-//                 if position == 0 {
-//                     count += 1;
-//                 }
-//             },
-//             Err(e) => {
-//                 eprintln!("Warning: Invalid rotation '{}': {}", line, e);
-//                 continue;
-//             }
-//         }
-//     }
-//
-//     count
-// }
+pub(crate) fn stop_count(new_position: i32, config: &DialConfig) -> u64 {
+    u64::from(new_position == config.target)
+}
+
+pub fn solve_puzzle_part1(input: &str, config: &DialConfig) -> SolveResult {
+    solve_part_with(input, config, Part::One, |_| {})
+}
 
 // ============================================================================
 // PART 2: Count zeros DURING each rotation (every click/step)
 // ============================================================================
 
-fn apply_rotation_with_zero_count(position: i32, direction: char, distance: i32) -> (i32, u32) {
-    let mut current = position;
-    let mut zero_count = 0;
+fn apply_rotation_counting(
+    target: i32,
+    position: i32,
+    direction: Direction,
+    distance: i64,
+    size: i32,
+) -> (i32, u64) {
+    // Zero (and negative) distances never step the dial, so there is nothing to count.
+    if distance <= 0 {
+        return (position, 0);
+    }
 
-    for _ in 0..distance {
-        match direction {
-            'R' => {
-                current = (current + 1) % 100;
-            }
-            'L' => {
-                current = (current - 1 + 100) % 100;
+    let target = i64::from(target);
+    let position = i64::from(position);
+    let size = i64::from(size);
+
+    // Every full revolution crosses the target exactly once; only the remainder
+    // depends on where the dial starts. Clicks needed to first reach the target
+    // are in 1..=size, so a remainder can add at most one more hit.
+    let full_revolutions = distance / size;
+    let remainder = distance % size;
+
+    let (new_position, first_hit) = match direction {
+        Direction::Right => (
+            (position + remainder) % size,
+            (target - position).rem_euclid(size),
+        ),
+        Direction::Left => (
+            (position - remainder).rem_euclid(size),
+            (position - target).rem_euclid(size),
+        ),
+    };
+    let first_hit = if first_hit == 0 { size } else { first_hit };
+
+    let hit_count = full_revolutions + i64::from(remainder >= first_hit);
+
+    (new_position as i32, hit_count as u64)
+}
+
+pub(crate) fn apply_rotation_with_zero_count(
+    position: i32,
+    direction: Direction,
+    distance: i64,
+    config: &DialConfig,
+) -> (i32, u64) {
+    apply_rotation_counting(
+        config.target,
+        position,
+        direction,
+        distance,
+        config.size as i32,
+    )
+}
+
+pub fn solve_puzzle_part2(input: &str, config: &DialConfig) -> SolveResult {
+    solve_part_with(input, config, Part::Two, |_| {})
+}
+
+pub fn solve_puzzle(input: &str, config: &DialConfig) -> SolveResult {
+    solve_puzzle_part2(input, config)
+}
+
+// Solves every `---`-separated puzzle on its own, each from `config.start`.
+pub fn solve_batch(input: &str, config: &DialConfig) -> Vec<SolveResult> {
+    batch_segments(input)
+        .into_iter()
+        .map(|segment| solve_puzzle(segment, config))
+        .collect()
+}
+
+// Part 2, but the first rotation that fails to parse aborts the solve; later
+// lines are never read.
+pub fn solve_strict(input: &str, config: &DialConfig) -> Result<SolveResult, (usize, ParseError)> {
+    solve_part_strict(input, config, Part::Two, RunOptions::default(), |_| {})
+}
+
+// For graders: whether `expected_zero_count` is the Part 2 answer for `input`.
+pub fn verify(input: &str, config: &DialConfig, expected_zero_count: u64) -> bool {
+    solve_puzzle(input, config).zero_count == expected_zero_count
+}
+
+// Same as `solve_puzzle`, but also reports every warning to `warnings`.
+pub fn solve_puzzle_to<W: Write>(
+    input: &str,
+    config: &DialConfig,
+    warnings: &mut W,
+) -> io::Result<SolveResult> {
+    let result = solve_puzzle(input, config);
+    write_warnings(&result.warnings, warnings)?;
+    Ok(result)
+}
+
+pub fn write_warnings<W: Write>(warnings: &[ParseError], out: &mut W) -> io::Result<()> {
+    for line in collapse_warnings(warnings) {
+        writeln!(out, "{}", line)?;
+    }
+
+    Ok(())
+}
+
+// Identical warnings are reported once, in first-seen order, with an `(x N)` suffix.
+pub fn collapse_warnings(warnings: &[ParseError]) -> Vec<String> {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut collapsed: Vec<(String, usize)> = Vec::new();
+
+    for warning in warnings {
+        let line = format_warning(warning);
+        match seen.get(&line) {
+            Some(&index) => collapsed[index].1 += 1,
+            None => {
+                seen.insert(line.clone(), collapsed.len());
+                collapsed.push((line, 1));
             }
-            _ => break,
         }
+    }
+
+    collapsed
+        .into_iter()
+        .map(|(line, count)| match count {
+            1 => line,
+            _ => format!("{} (x {})", line, count),
+        })
+        .collect()
+}
+
+pub fn format_warning(warning: &ParseError) -> String {
+    format!(
+        "Warning: Invalid rotation '{}': {}",
+        warning.line(),
+        warning
+    )
+}
+
+// Hands every non-blank, non-comment line to `parser` whole, instead of
+// splitting it into tokens and directives for `parse_rotation`.
+pub fn solve_puzzle_with<P>(input: &str, config: &DialConfig, parser: P) -> SolveResult
+where
+    P: Fn(&str) -> Result<(Direction, i64), ParseError>,
+{
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
 
-        if current == 0 {
-            zero_count += 1;
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
+            continue;
         }
+        run.take(index + 1, parser(line));
     }
 
-    (current, zero_count)
+    run.result
 }
 
-pub fn solve_puzzle(input: &str) -> u32 {
-    let mut position = 50;
-    let mut count = 0;
+// Solves already-parsed rotations; each one counts as its own line. Text input
+// goes through the same `Run` as these, so the answers always agree.
+pub fn solve_rotations(rotations: &[(Direction, i64)], config: &DialConfig) -> SolveResult {
+    solve_parsed(rotations.iter().copied().map(Ok), config)
+}
 
-    for line in input.lines() {
-        let line = line.trim();
+// Like `solve_rotations`, but rotations that failed to parse elsewhere become
+// warnings the way they do for text input.
+pub fn solve_parsed<I>(rotations: I, config: &DialConfig) -> SolveResult
+where
+    I: IntoIterator<Item = Result<(Direction, i64), ParseError>>,
+{
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
 
-        if line.is_empty() {
+    for (index, rotation) in rotations.into_iter().enumerate() {
+        run.take(index + 1, rotation);
+    }
+
+    run.result
+}
+
+// Undoes the input: starting where it leaves the dial, applies its rotations
+// last to first with each direction flipped, so the dial ends back on
+// `config.start`. RESET and SET lines cannot be undone and are ignored.
+pub fn solve_reversed(input: &str, config: &DialConfig) -> SolveResult {
+    let undo = DialConfig {
+        start: final_position(input, config),
+        ..*config
+    };
+    let mut run = Run::new(&undo, Part::Two, |_: &Step| {});
+    let forward: Vec<_> = config_rotations(input, *config).collect();
+
+    for (index, rotation) in forward.into_iter().rev().enumerate() {
+        let flipped = rotation.map(|(direction, distance)| (direction.reversed(), distance));
+        run.take(index + 1, flipped);
+    }
+
+    run.result
+}
+
+// The shortest rotation that takes the dial from where the input leaves it back
+// to `config.start`, written so it can be appended to the input. When the dial
+// is exactly half a turn away both ways are equally short and `R` wins; when it
+// is already on the start this is `R0`. The distance is in clicks, even with
+// `degrees` or `scale` set. The config is validated before anything is read.
+pub fn closing_rotation(input: &str, config: &DialConfig) -> Result<(Direction, i64), ConfigError> {
+    config.validate()?;
+    let size = i64::from(config.size);
+    let clockwise =
+        (i64::from(config.start) - i64::from(final_position(input, config))).rem_euclid(size);
+
+    Ok(if clockwise * 2 <= size {
+        (config.turn(Direction::Right), clockwise)
+    } else {
+        (config.turn(Direction::Left), size - clockwise)
+    })
+}
+
+// Applies every rotation but only counts zeros from those matching `pred`.
+pub fn solve_filtered<F>(input: &str, config: &DialConfig, pred: F) -> SolveResult
+where
+    F: Fn(Direction, i64) -> bool,
+{
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
+    run.tally = Some(&pred);
+
+    for (index, line) in input.lines().enumerate() {
+        run.process_line(index + 1, line);
+    }
+
+    run.result
+}
+
+// The answer under every reading of an ambiguous statement: the outer index is
+// clockwise meaning `R` (0) or `L` (1), the inner one counting passes through
+// the target (Part 2, 0) or only stops on it (Part 1, 1). `config` supplies the
+// rest of the dial; its own `clockwise_is_right` is overridden.
+pub fn solve_all_conventions(input: &str, config: &DialConfig) -> [[SolveResult; 2]; 2] {
+    [true, false].map(|clockwise_is_right| {
+        let config = DialConfig {
+            clockwise_is_right,
+            ..*config
+        };
+        [
+            solve_puzzle_part2(input, &config),
+            solve_puzzle_part1(input, &config),
+        ]
+    })
+}
+
+// Part 2 with every position in `targets` counting as a target, in place of
+// `config.target`. Targets outside the dial, as DIAL lines leave it, never match.
+pub fn solve_multi_target(input: &str, config: &DialConfig, targets: &[i32]) -> SolveResult {
+    let marked = |size: usize| {
+        let mut special = vec![false; size];
+        for &target in targets {
+            if let Some(slot) = usize::try_from(target)
+                .ok()
+                .and_then(|t| special.get_mut(t))
+            {
+                *slot = true;
+            }
+        }
+        let per_turn = special.iter().filter(|&&s| s).count() as u64;
+        (special, per_turn)
+    };
+    let mut size = config.size as usize;
+    let (mut special, mut per_turn) = marked(size);
+
+    let mut passes = 0u64;
+    let mut stops = 0;
+    let mut result = walk(input, config, |step| {
+        if step.size as usize != size {
+            size = step.size as usize;
+            (special, per_turn) = marked(size);
+        }
+        // Whole turns pass every target; only the remainder is stepped.
+        let turns = (step.distance as u64) / size as u64;
+        let remainder = (step.distance as u64 % size as u64) as usize;
+        let from = step.from as usize;
+        let hits = (1..=remainder)
+            .map(|click| match step.direction {
+                Direction::Left => (from + size - click) % size,
+                Direction::Right => (from + click) % size,
+            })
+            .filter(|&position| special[position])
+            .count() as u64;
+
+        passes = passes.saturating_add(turns.saturating_mul(per_turn).saturating_add(hits));
+        stops += u64::from(special.get(step.to as usize) == Some(&true));
+    });
+
+    result.zero_count = passes;
+    result.update_zero_fraction();
+    result.passes_through_zero = passes;
+    result.stops_on_zero = stops;
+    result
+}
+
+// Part 2 where clicks on a taped-over position never count. Only the target is
+// counted at all, so a dead zone matters only when it covers the target.
+pub fn solve_with_dead_zones(input: &str, config: &DialConfig, dead_zones: &[i32]) -> SolveResult {
+    let target = [config.target];
+    let targets: &[i32] = if dead_zones.contains(&config.target) {
+        &[]
+    } else {
+        &target
+    };
+
+    solve_multi_target(input, config, targets)
+}
+
+// Each line is an absolute position the dial sweeps to the shorter way,
+// counting the zeros it crosses. Half a turn away is a tie and goes up through
+// the positions, which is clockwise unless `mirrored`. Positions are clicks:
+// `degrees` and `scale` do not apply.
+pub fn solve_targets(input: &str, config: &DialConfig) -> SolveResult {
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
+    let size = i64::from(config.size);
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(COMMENT_PREFIX) {
             continue;
         }
 
-        match parse_rotation(line) {
-            Ok((direction, distance)) => {
-                let (new_position, zeros_during_rotation) =
-                    apply_rotation_with_zero_count(position, direction, distance);
-                position = new_position;
-                count += zeros_during_rotation;
+        let target = match line.parse::<i64>() {
+            Ok(target) if (0..size).contains(&target) => target,
+            Ok(_) => {
+                run.take(
+                    index + 1,
+                    Err(ParseError::InvalidDirective {
+                        line: line.to_string(),
+                        reason: format!("position must be between 0 and {}", size - 1),
+                    }),
+                );
+                continue;
             }
-            Err(e) => {
-                eprintln!("Warning: Invalid rotation '{}': {}", line, e);
+            Err(source) => {
+                run.take(
+                    index + 1,
+                    Err(ParseError::InvalidNumber {
+                        line: line.to_string(),
+                        source,
+                        offset: 0,
+                    }),
+                );
                 continue;
             }
+        };
+
+        let clockwise = (target - i64::from(run.result.final_position)).rem_euclid(size);
+        match clockwise {
+            0 => {}
+            d if d * 2 <= size => run.apply(index + 1, Direction::Right, d),
+            d => run.apply(index + 1, Direction::Left, size - d),
+        }
+    }
+
+    run.result
+}
+
+// The 1-based line after which the two inputs leave the dial in different
+// places. If one input is a prefix of the other by that measure, the first
+// line past the shorter one is reported; `None` if they agree throughout.
+pub fn first_divergence(a: &str, b: &str, config: &DialConfig) -> Option<usize> {
+    let (a, b) = (line_positions(a, config), line_positions(b, config));
+
+    match a.iter().zip(&b).position(|(a, b)| a != b) {
+        Some(index) => Some(index + 1),
+        None if a.len() != b.len() => Some(a.len().min(b.len()) + 1),
+        None => None,
+    }
+}
+
+// Where the dial is after each line, including blank lines and RESET/SET.
+fn line_positions(input: &str, config: &DialConfig) -> Vec<i32> {
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
+
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| {
+            run.process_line(index + 1, line);
+            run.result.final_position
+        })
+        .collect()
+}
+
+// Memoizes rotation outcomes, for inputs with long runs of identical rotations.
+// Opt-in: each rotation is already a few integer operations, so the lookup only
+// pays off when the same rotations repeat from the same positions.
+pub fn solve_puzzle_cached(input: &str, config: &DialConfig) -> SolveResult {
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
+    run.cache = Some(RotationCache::default());
+
+    for (index, line) in input.lines().enumerate() {
+        run.process_line(index + 1, line);
+    }
+
+    run.result
+}
+
+pub fn solve_from_reader<R: BufRead>(
+    mut reader: R,
+    config: &DialConfig,
+) -> io::Result<SolveResult> {
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
+    let mut line = String::new();
+    let mut line_number = 0;
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            break;
         }
+
+        line_number += 1;
+        run.process_line(line_number, &line);
+    }
+
+    Ok(run.result)
+}
+
+pub fn solve_with_trace(input: &str, config: &DialConfig) -> (SolveResult, Vec<i32>) {
+    let mut trace = Vec::new();
+    let result = walk(input, config, |step| trace.push(step.to));
+    (result, trace)
+}
+
+// Clicks until turning `direction` from `position` next lands on zero; from
+// zero itself that is a whole turn. An empty dial has no zero to reach.
+pub fn clicks_to_zero(
+    position: i32,
+    direction: Direction,
+    dial_size: u32,
+) -> Result<u32, ConfigError> {
+    if dial_size == 0 {
+        return Err(ConfigError::EmptyDial);
+    }
+    let size = i64::from(dial_size);
+    let position = i64::from(position).rem_euclid(size);
+    let clicks = match direction {
+        Direction::Left => position,
+        Direction::Right => size - position,
+    };
+
+    Ok(match clicks {
+        0 => dial_size,
+        clicks => clicks as u32,
+    })
+}
+
+// How many times the same rotation repeats before the dial is back where it
+// began: `dial_size / gcd(distance, dial_size)`. Direction does not matter. An
+// empty dial has no period.
+pub fn rotation_period(distance: i64, dial_size: u32) -> Result<u32, ConfigError> {
+    if dial_size == 0 {
+        return Err(ConfigError::EmptyDial);
+    }
+    // Less than `dial_size` once reduced, so it fits in a u32.
+    let reduced = (distance.unsigned_abs() % u64::from(dial_size)) as u32;
+    let (mut a, mut b) = (dial_size, reduced);
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+
+    Ok(dial_size / a)
+}
+
+// Infers the shortest rotation between consecutive trace positions. A trace
+// cannot tell a rotation from one that also spins whole turns, and two cases
+// are ambiguous: a move of exactly half the dial becomes `R`, and staying put
+// becomes a full turn `R<dial_size>` (the solver skips `R0`, so it would never
+// leave a trace entry). An empty dial is rejected before anything is read.
+pub fn trace_to_rotations(
+    trace: &[i32],
+    start: i32,
+    dial_size: u32,
+) -> Result<Vec<(Direction, i64)>, ConfigError> {
+    if dial_size == 0 {
+        return Err(ConfigError::EmptyDial);
     }
+    let size = i64::from(dial_size);
+    let mut from = start;
+
+    Ok(trace
+        .iter()
+        .map(|&to| {
+            let clockwise = (i64::from(to) - i64::from(from)).rem_euclid(size);
+            from = to;
+            match clockwise {
+                0 => (Direction::Right, size),
+                d if d * 2 <= size => (Direction::Right, d),
+                d => (Direction::Left, size - d),
+            }
+        })
+        .collect())
+}
+
+pub fn solve_puzzle_verbose(input: &str, config: &DialConfig, part: Part) -> SolveResult {
+    solve_part_with(input, config, part, print_step)
+}
+
+pub(crate) fn print_step(step: &Step) {
+    eprintln!("{}{} -> {}", step.direction, step.distance, step.to);
+}
+
+// Stops after `limit` applied rotations; blank lines, comments and lines that
+// fail to parse do not count towards it.
+pub fn solve_puzzle_limited(
+    input: &str,
+    config: &DialConfig,
+    part: Part,
+    limit: usize,
+) -> SolveResult {
+    let options = RunOptions {
+        limit: Some(limit),
+        ..RunOptions::default()
+    };
+    solve_part_with_options(input, config, part, options, |_| {})
+}
+
+// One sentence per rotation that produced a zero, in a stable wording.
+pub fn solve_puzzle_explained(
+    input: &str,
+    config: &DialConfig,
+    part: Part,
+) -> (SolveResult, Vec<String>) {
+    explain_with_options(input, config, part, RunOptions::default())
+}
+
+pub(crate) fn explain_with_options(
+    input: &str,
+    config: &DialConfig,
+    part: Part,
+    options: RunOptions,
+) -> (SolveResult, Vec<String>) {
+    let mut explanations = Vec::new();
+    let result = solve_part_with_options(input, config, part, options, |step| {
+        if step.zeros > 0 {
+            explanations.push(explain(step));
+        }
+    });
+    (result, explanations)
+}
+
+fn explain(step: &Step) -> String {
+    let times = match step.zeros {
+        1 => "once".to_string(),
+        2 => "twice".to_string(),
+        n => format!("{} times", n),
+    };
 
-    count
+    format!(
+        "rotation {}{} starting at {} crossed zero {}, ending at {}.",
+        step.direction, step.distance, step.from, times, step.to
+    )
+}
+
+// RESET lines are not rotations and are skipped here. Fractions such as
+// `R1/4` are taken of the default 100-click dial.
+pub fn rotations(input: &str) -> impl Iterator<Item = Result<(Direction, i64), ParseError>> + '_ {
+    config_rotations(input, DialConfig::default())
+}
+
+fn config_rotations(
+    input: &str,
+    config: DialConfig,
+) -> impl Iterator<Item = Result<(Direction, i64), ParseError>> + '_ {
+    input
+        .lines()
+        .flat_map(move |line| line_rotations(line, &config))
+}
+
+// Folds adjacent same-direction rotations together (`R5 R3` becomes `R8`) and
+// drops zero-distance ones. Part 2 counts and the final position are unchanged,
+// but stops on the target between merged rotations are lost, so Part 1 can differ.
+pub fn coalesce(rotations: &[(Direction, i64)]) -> Vec<(Direction, i64)> {
+    let mut coalesced: Vec<(Direction, i64)> = Vec::new();
+
+    for &(direction, distance) in rotations {
+        if distance == 0 {
+            continue;
+        }
+
+        match coalesced.last_mut() {
+            Some((last, total)) if *last == direction && total.checked_add(distance).is_some() => {
+                *total += distance
+            }
+            _ => coalesced.push((direction, distance)),
+        }
+    }
+
+    coalesced
+}
+
+// Shrinks the input to fewer rotations with the same Part 2 count, for bug
+// reports. Chunks of rotations are dropped whenever the count survives, halving
+// the chunk size down to single rotations, so the result is smaller but not
+// necessarily minimal. RESET and SET lines are dropped. `None` if the input's
+// rotations do not count `target_count` zeros to begin with.
+pub fn minimize(
+    input: &str,
+    config: &DialConfig,
+    target_count: u64,
+) -> Option<Vec<(Direction, i64)>> {
+    let count = |rotations: &[(Direction, i64)]| {
+        solve_parsed(rotations.iter().copied().map(Ok), config).zero_count
+    };
+    let mut kept: Vec<(Direction, i64)> = config_rotations(input, *config)
+        .filter_map(Result::ok)
+        .collect();
+    if count(&kept) != target_count {
+        return None;
+    }
+
+    let mut chunk = kept.len().div_ceil(2);
+    while chunk > 0 {
+        let mut index = 0;
+        while index < kept.len() {
+            let end = (index + chunk).min(kept.len());
+            let candidate = [&kept[..index], &kept[end..]].concat();
+            if count(&candidate) == target_count {
+                kept = candidate;
+            } else {
+                index = end;
+            }
+        }
+        chunk /= 2;
+    }
+
+    Some(kept)
+}
+
+// Where the dial ends up, without counting any zeros. The signed travel is
+// summed as an i128 and wrapped only once at the end: each rotation adds at most
+// i64::MAX, so overflowing it would take more than 2^64 rotations.
+pub fn final_position(input: &str, origin: &DialConfig) -> i32 {
+    let mut config = *origin;
+    let mut net: i128 = 0;
+
+    for line in input.lines() {
+        let parsed = parse_line(line, &config);
+        if let Some(Ok(size)) = parsed.resize(&config) {
+            let position = (i128::from(config.start) + net).rem_euclid(i128::from(config.size));
+            let position = remap_position(position as i32, config.size, size);
+            config = origin.resized(size);
+            net = i128::from(position) - i128::from(config.start);
+            continue;
+        }
+        match parsed.jump(&config) {
+            Some(Ok(position)) => {
+                net = i128::from(position) - i128::from(config.start);
+                continue;
+            }
+            Some(Err(_)) => continue,
+            None => {}
+        }
+
+        for (direction, distance) in parsed.rotations().filter_map(Result::ok) {
+            net += signed_distance(config.turn(direction), config.clicks(distance));
+        }
+    }
+
+    (i128::from(config.start) + net).rem_euclid(i128::from(config.size)) as i32
+}
+
+// Picks up counting from an earlier (Part 2) result, as if solving had never stopped.
+pub(crate) fn solve_continuing(
+    input: &str,
+    config: &DialConfig,
+    earlier: SolveResult,
+) -> SolveResult {
+    let mut run = Run::new(config, Part::Two, |_: &Step| {});
+    if earlier.dial_size != 0 {
+        run.config = config.resized(earlier.dial_size);
+    }
+    run.result = earlier;
+
+    for (index, line) in input.lines().enumerate() {
+        run.process_line(index + 1, line);
+    }
+
+    run.result
+}
+
+// Stops reading as soon as a line leaves the dial on the target.
+pub fn first_zero_line(input: &str, config: &DialConfig) -> Option<usize> {
+    let mut run = Run::new(config, Part::One, |_: &Step| {});
+
+    for (index, line) in input.lines().enumerate() {
+        run.process_line(index + 1, line);
+        if run.result.stops_on_zero > 0 {
+            return Some(index + 1);
+        }
+    }
+
+    None
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InputCheck {
+    pub valid_lines: usize,
+    pub invalid_lines: usize,
+    pub errors: Vec<(usize, ParseError)>,
+}
+
+pub fn check_input(input: &str) -> InputCheck {
+    let mut check = InputCheck::default();
+
+    for (index, line) in input.lines().enumerate() {
+        // A REPEAT body or multiplied rotation is checked once, however many times it would run.
+        let rotations: Vec<_> = match parse_line(line, &DialConfig::default()) {
+            ParsedLine::Tokens(tokens) => tokens.map(|(rotation, _)| rotation).collect(),
+            ParsedLine::Repeat { body, .. } => {
+                body.into_iter().map(|(rotation, _)| rotation).collect()
+            }
+            ParsedLine::Reset | ParsedLine::Set { .. } | ParsedLine::Dial { .. } => {
+                check.valid_lines += 1;
+                continue;
+            }
+            ParsedLine::Invalid(e) => vec![Err(e)],
+        };
+        if rotations.is_empty() {
+            continue;
+        }
+
+        let errors_before = check.errors.len();
+        for rotation in rotations {
+            if let Err(e) = rotation {
+                check.errors.push((index + 1, e));
+            }
+        }
+
+        if check.errors.len() == errors_before {
+            check.valid_lines += 1;
+        } else {
+            check.invalid_lines += 1;
+        }
+    }
+
+    check
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkipReason {
+    Blank,
+    Comment,
+    Invalid(ParseError),
+}
+
+// Every line (or, for parse errors, every bad token) that moved nothing, in
+// input order. Parse errors are reported as by `check_input`.
+pub fn skip_reasons(input: &str) -> Vec<(usize, SkipReason)> {
+    let mut errors = check_input(input).errors.into_iter().peekable();
+    let mut reasons = Vec::new();
+
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+
+        if line.is_empty() {
+            reasons.push((line_number, SkipReason::Blank));
+        } else if line.starts_with(COMMENT_PREFIX) {
+            reasons.push((line_number, SkipReason::Comment));
+        }
+
+        while let Some((_, e)) = errors.next_if(|(error_line, _)| *error_line == line_number) {
+            reasons.push((line_number, SkipReason::Invalid(e)));
+        }
+    }
+
+    reasons
+}
+
+pub(crate) fn signed_distance(direction: Direction, distance: i64) -> i128 {
+    i128::from(direction.sign()) * i128::from(distance)
+}
+
+pub(crate) fn full_revolutions(net_offset: i128, dial_size: u32) -> u64 {
+    match dial_size {
+        0 => 0,
+        size => u64::try_from(net_offset.unsigned_abs() / u128::from(size)).unwrap_or(u64::MAX),
+    }
+}
+
+// ============================================================================
+// Shared line processing for both parts
+// ============================================================================
+
+// `direction` is the effective turn, after `DialConfig::turn`, and `size` is
+// the dial it turned, after any DIAL lines.
+pub(crate) struct Step {
+    pub line_number: usize,
+    pub direction: Direction,
+    pub distance: i64,
+    pub from: i32,
+    pub to: i32,
+    pub zeros: u64,
+    pub size: u32,
+}
+
+// Runs the Part 2 solver, handing every applied rotation to `observe`.
+pub(crate) fn walk<O>(input: &str, config: &DialConfig, observe: O) -> SolveResult
+where
+    O: FnMut(&Step),
+{
+    solve_part_with(input, config, Part::Two, observe)
+}
+
+fn solve_part_with<O>(input: &str, config: &DialConfig, part: Part, observe: O) -> SolveResult
+where
+    O: FnMut(&Step),
+{
+    solve_part_with_options(input, config, part, RunOptions::default(), observe)
+}
+
+// A flag a signal handler or another thread sets to stop a run early. Two are
+// equal when they watch the same flag.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Interrupt(pub &'static AtomicBool);
+
+impl PartialEq for Interrupt {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub(crate) struct RunOptions {
+    // Stop after this many applied rotations.
+    pub limit: Option<usize>,
+    // Stop once this many clicks have been stepped, cutting the last rotation short.
+    pub max_clicks: Option<u64>,
+    // Drop rotations with an unknown direction without a warning.
+    pub ignore_unknown: bool,
+    // The distance a bare `L` or `R` turns, instead of being too short.
+    pub default_distance: Option<i64>,
+    // Written distances outside `min..=max` are rejected with a warning.
+    pub distance_range: Option<(i64, i64)>,
+    // Stop at the first rotation that fails to parse.
+    pub strict: bool,
+    // Checked before every rotation; once set, the run stops where it is.
+    pub interrupt: Option<Interrupt>,
+    // The dial size and position an earlier run stopped at, to carry on from
+    // rather than starting afresh. RESET still returns to the configured start.
+    pub resume: Option<(u32, i32)>,
+}
+
+pub(crate) fn solve_part_with_options<O>(
+    input: &str,
+    config: &DialConfig,
+    part: Part,
+    options: RunOptions,
+    observe: O,
+) -> SolveResult
+where
+    O: FnMut(&Step),
+{
+    let mut run = Run::with_options(config, part, options, observe);
+
+    for (index, line) in input.lines().enumerate() {
+        if run.exhausted() {
+            break;
+        }
+        run.process_line(index + 1, line);
+    }
+
+    run.result
+}
+
+// Like `solve_part_with_options` with `strict` set, but hands back the line
+// number and error of the failure instead of the partial result.
+pub(crate) fn solve_part_strict<O>(
+    input: &str,
+    config: &DialConfig,
+    part: Part,
+    options: RunOptions,
+    observe: O,
+) -> Result<SolveResult, (usize, ParseError)>
+where
+    O: FnMut(&Step),
+{
+    let options = RunOptions {
+        strict: true,
+        ..options
+    };
+    let mut run = Run::with_options(config, part, options, observe);
+
+    for (index, line) in input.lines().enumerate() {
+        run.process_line(index + 1, line);
+        if let Some(e) = run.result.warnings.pop() {
+            return Err((index + 1, e));
+        }
+        if run.exhausted() {
+            break;
+        }
+    }
+
+    Ok(run.result)
+}
+
+struct Run<'a, O> {
+    // The dial as DIAL lines have left it, and the one it was configured as.
+    config: DialConfig,
+    origin: DialConfig,
+    part: Part,
+    observe: O,
+    cache: Option<RotationCache>,
+    // Rotations it rejects still move the dial, but their zeros are not counted.
+    tally: Option<&'a dyn Fn(Direction, i64) -> bool>,
+    options: RunOptions,
+    result: SolveResult,
+}
+
+impl<'a, O> Run<'a, O>
+where
+    O: FnMut(&Step),
+{
+    fn new(config: &'a DialConfig, part: Part, observe: O) -> Self {
+        Run {
+            config: *config,
+            origin: *config,
+            part,
+            observe,
+            cache: None,
+            tally: None,
+            options: RunOptions::default(),
+            result: SolveResult::initial(config),
+        }
+    }
+
+    fn with_options(config: &'a DialConfig, part: Part, options: RunOptions, observe: O) -> Self {
+        let mut run = Run::new(config, part, observe);
+        run.options = options;
+        if let Some((size, position)) = options.resume {
+            run.config = config.resized(size);
+            run.result = SolveResult {
+                final_position: position,
+                dial_size: size,
+                ..SolveResult::default()
+            };
+        }
+
+        run
+    }
+
+    fn exhausted(&self) -> bool {
+        let failed = self.options.strict && self.result.lines_skipped > 0;
+        failed
+            || self
+                .options
+                .limit
+                .is_some_and(|limit| self.result.rotations_applied >= limit)
+            || self
+                .options
+                .max_clicks
+                .is_some_and(|max| self.result.clicks >= max)
+            || self
+                .options
+                .interrupt
+                .is_some_and(|Interrupt(flag)| flag.load(Ordering::Relaxed))
+    }
+
+    fn process_line(&mut self, line_number: usize, line: &str) {
+        let parsed = parse_line(line, &self.config);
+        match parsed.resize(&self.config) {
+            Some(Ok(size)) if !self.exhausted() => return self.resize(size),
+            Some(Ok(_)) => return,
+            Some(Err(e)) => return self.take(line_number, Err(e)),
+            None => {}
+        }
+        match parsed.jump(&self.config) {
+            Some(Ok(position)) if !self.exhausted() => {
+                self.result.final_position = position;
+                return;
+            }
+            Some(Ok(_)) => return,
+            Some(Err(e)) => return self.take(line_number, Err(e)),
+            None => {}
+        }
+
+        for rotation in parsed.rotations() {
+            if self.exhausted() {
+                break;
+            }
+            self.take(line_number, rotation);
+        }
+    }
+
+    fn take(&mut self, line_number: usize, rotation: Result<(Direction, i64), ParseError>) {
+        if self.exhausted() {
+            return;
+        }
+
+        let rotation = match (rotation, self.options.default_distance) {
+            (Err(ParseError::TooShort { line }), Some(distance))
+                if matches!(line.as_str(), "L" | "R" | "l" | "r") =>
+            {
+                parse_rotation(&format!("{}{}", line, distance))
+            }
+            (rotation, _) => rotation,
+        };
+
+        let rotation = match (rotation, self.options.distance_range) {
+            (Ok((direction, distance)), Some((min, max))) if !(min..=max).contains(&distance) => {
+                Err(ParseError::DistanceOutOfRange {
+                    line: format!("{}{}", direction, distance),
+                    min,
+                    max,
+                })
+            }
+            (rotation, _) => rotation,
+        };
+
+        let rotation =
+            rotation.map(|(direction, distance)| (self.config.turn(direction), distance));
+
+        match rotation {
+            // `R0`/`L0` leave the dial untouched: it is not counted as an
+            // applied rotation and cannot stop on the target either.
+            Ok((_, 0)) => {}
+            Ok((direction, distance)) => match self.config.clicks(distance) {
+                0 => {}
+                clicks => {
+                    let clicks = match self.options.max_clicks {
+                        Some(max) => clicks.min((max - self.result.clicks) as i64),
+                        None => clicks,
+                    };
+                    self.apply(line_number, direction, clicks)
+                }
+            },
+            Err(ParseError::InvalidDirection { .. }) if self.options.ignore_unknown => {}
+            Err(e) => {
+                self.result.lines_skipped += 1;
+                self.result.warnings.push(e);
+            }
+        }
+    }
+
+    // Crossings from here on are counted on the new dial. Cached rotations
+    // were worked out on the old one.
+    fn resize(&mut self, size: u32) {
+        let position = remap_position(self.result.final_position, self.config.size, size);
+        self.config = self.origin.resized(size);
+        self.result.final_position = position;
+        self.result.dial_size = size;
+        if self.cache.is_some() {
+            self.cache = Some(RotationCache::default());
+        }
+    }
+
+    fn apply(&mut self, line_number: usize, direction: Direction, distance: i64) {
+        let result = &mut self.result;
+
+        let from = result.final_position;
+        let config = &self.config;
+        let compute = || apply_rotation_with_zero_count(from, direction, distance, config);
+        let (new_position, passes) = match &mut self.cache {
+            Some(cache) => cache.get_or_insert_with((from, direction, distance), compute),
+            None => compute(),
+        };
+
+        // A position off the dial means the arithmetic above is broken: fail loudly
+        // in debug builds, and wrap it back with a warning in release builds.
+        let size = self.config.size as i32;
+        let in_bounds = (0..size).contains(&new_position);
+        debug_assert!(
+            in_bounds,
+            "position {} left the dial 0..{}",
+            new_position, size
+        );
+        let new_position = if in_bounds {
+            new_position
+        } else {
+            result.warnings.push(ParseError::PositionOffDial {
+                line: format!("{}{}", direction, distance),
+                position: new_position,
+                size: self.config.size,
+            });
+            new_position.rem_euclid(size)
+        };
+        let stops = stop_count(new_position, &self.config);
+        let (passes, stops) = match self.tally {
+            Some(tally) if !tally(direction, distance) => (0, 0),
+            _ => (passes, stops),
+        };
+        let zeros_during_rotation = match self.part {
+            Part::One => stops,
+            Part::Two => passes,
+        };
+
+        // Saturate rather than wrap: a handful of near-i64::MAX distances can
+        // exceed even a u64 count.
+        result.final_position = new_position;
+        result.zero_count = result.zero_count.saturating_add(zeros_during_rotation);
+        result.passes_through_zero = result.passes_through_zero.saturating_add(passes);
+        result.stops_on_zero += stops;
+        result.rotations_applied += 1;
+        result.clicks = result.clicks.saturating_add(distance as u64);
+        result.add_distance(direction, distance);
+
+        result.net_offset += signed_distance(direction, distance);
+        result.full_revolutions = full_revolutions(result.net_offset, result.dial_size);
+        result.update_zero_fraction();
+
+        (self.observe)(&Step {
+            line_number,
+            direction,
+            distance,
+            from,
+            to: new_position,
+            zeros: zeros_during_rotation,
+            size: self.config.size,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::generator::XorShift;
+    use Direction::{Left, Right};
+
+    fn apply_rotation_step_by_step(
+        position: i32,
+        direction: Direction,
+        distance: i64,
+        size: i32,
+    ) -> (i32, u64) {
+        apply_rotation_step_by_step_to(0, position, direction, distance, size)
+    }
+
+    fn apply_rotation_step_by_step_to(
+        target: i32,
+        position: i32,
+        direction: Direction,
+        distance: i64,
+        size: i32,
+    ) -> (i32, u64) {
+        let mut current = position;
+        let mut zero_count = 0;
+
+        for _ in 0..distance {
+            match direction {
+                Right => current = (current + 1) % size,
+                Left => current = (current - 1 + size) % size,
+            }
+
+            if current == target {
+                zero_count += 1;
+            }
+        }
+
+        (current, zero_count)
+    }
+
+    #[test]
+    fn fast_rotation_matches_step_by_step() {
+        let mut rng = XorShift(0x2025_1201);
+
+        for _ in 0..5_000 {
+            let size = 1 + rng.below(400) as i32;
+            let position = rng.below(size as u64) as i32;
+            let direction = if rng.below(2) == 0 { Left } else { Right };
+            let distance = rng.below(2_000) as i64;
+
+            assert_eq!(
+                apply_rotation_counting(0, position, direction, distance, size),
+                apply_rotation_step_by_step(position, direction, distance, size),
+                "{}{} from {} on a {}-dial",
+                direction,
+                distance,
+                position,
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn huge_distance_is_computed_without_stepping() {
+        assert_eq!(
+            apply_rotation_counting(0, 50, Right, 1_000_000_000, 100),
+            (50, 10_000_000)
+        );
+    }
+
+    #[test]
+    fn distances_beyond_i32_do_not_overflow() {
+        let distance = i64::from(i32::MAX) * 4;
+        assert_eq!(
+            parse_rotation(&format!("L{}", distance)),
+            Ok((Left, distance))
+        );
+
+        let (position, zeros) = apply_rotation_counting(0, 50, Left, distance, 100);
+        assert_eq!(position, (50 - distance).rem_euclid(100) as i32);
+        assert_eq!(zeros, 85_899_346);
+    }
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn parts_count_the_documented_example_differently() {
+        let config = DialConfig::default();
+
+        let part1 = solve_puzzle_part1(EXAMPLE, &config);
+        let part2 = solve_puzzle_part2(EXAMPLE, &config);
+
+        assert_eq!(part1.zero_count, 3);
+        assert_eq!(part2.zero_count, 6);
+        assert_eq!(part1.final_position, part2.final_position);
+        assert_eq!(part1.final_position, 32);
+    }
+
+    #[test]
+    fn malformed_lines_are_collected_as_warnings() {
+        let result = solve_puzzle("R10\nX5\n\nRabc\nL10\n", &DialConfig::default());
+
+        assert_eq!(result.rotations_applied, 2);
+        assert_eq!(result.lines_skipped, 2);
+        assert_eq!(result.warnings.len(), 2);
+        assert!(matches!(
+            result.warnings[0],
+            ParseError::InvalidDirection { found: 'X', .. }
+        ));
+        assert!(matches!(
+            result.warnings[1],
+            ParseError::InvalidNumber { .. }
+        ));
+    }
+
+    #[test]
+    fn clean_input_has_no_warnings() {
+        let result = solve_puzzle(EXAMPLE, &DialConfig::default());
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn multiple_rotations_per_line_keep_their_order() {
+        let packed = "L68 L30\nR48\nL5 R60 L55\nL1 L99 R14 L82\n";
+        let config = DialConfig::default();
+
+        assert_eq!(
+            solve_puzzle(packed, &config),
+            solve_puzzle(EXAMPLE, &config)
+        );
+        assert_eq!(
+            solve_puzzle_part1(packed, &config),
+            solve_puzzle_part1(EXAMPLE, &config)
+        );
+    }
+
+    #[test]
+    fn comment_lines_are_skipped_silently() {
+        let commented = "# sample from the puzzle\nL68\nL30\n  # halfway there\nR48\nL5\nR60\nL55\n#\nL1\nL99\nR14\nL82\n";
+        let config = DialConfig::default();
+
+        let result = solve_puzzle(commented, &config);
+
+        assert_eq!(result, solve_puzzle(EXAMPLE, &config));
+        assert!(result.warnings.is_empty());
+    }
+
+    #[test]
+    fn lowercase_directions_are_accepted() {
+        assert_eq!(parse_rotation("r5"), parse_rotation("R5"));
+        assert_eq!(parse_rotation("l5"), Ok((Left, 5)));
+        assert!(matches!(
+            parse_rotation("x5"),
+            Err(ParseError::InvalidDirection { found: 'x', .. })
+        ));
+
+        let config = DialConfig::default();
+        assert_eq!(
+            solve_puzzle(&EXAMPLE.to_lowercase(), &config),
+            solve_puzzle(EXAMPLE, &config)
+        );
+    }
+
+    #[test]
+    fn signed_offsets_map_to_directions() {
+        assert_eq!(parse_rotation("+10"), Ok((Right, 10)));
+        assert_eq!(parse_rotation("-5"), Ok((Left, 5)));
+        assert!(matches!(
+            parse_rotation("10"),
+            Err(ParseError::InvalidDirection { found: '1', .. })
+        ));
+
+        let config = DialConfig::default();
+        let mixed = "-68\nL30\n+48\nL5\n+60 -55\nL1\n-99\nR14\n-82\n";
+        assert_eq!(solve_puzzle(mixed, &config), solve_puzzle(EXAMPLE, &config));
+    }
+
+    #[test]
+    fn trace_records_position_after_each_rotation() {
+        let (result, trace) = solve_with_trace(EXAMPLE, &DialConfig::default());
+
+        assert_eq!(trace, vec![82, 52, 0, 95, 55, 0, 99, 0, 14, 32]);
+        assert_eq!(trace.len(), result.rotations_applied);
+        assert_eq!(trace.last(), Some(&result.final_position));
+    }
+
+    #[test]
+    fn traces_round_trip_into_rotations() {
+        let config = DialConfig::default();
+        let input = "R10\nL49\nL3\nR50\nR100\n";
+        let (_, trace) = solve_with_trace(input, &config);
+
+        let rotations = trace_to_rotations(&trace, config.start, config.size).unwrap();
+        assert_eq!(
+            rotations,
+            [
+                (Right, 10),
+                (Left, 49),
+                (Left, 3),
+                (Right, 50),
+                (Right, 100)
+            ]
+        );
+        assert_eq!(
+            solve_rotations(&rotations, &config),
+            solve_puzzle(input, &config)
+        );
+
+        // Only the positions survive when a rotation is longer than necessary.
+        let (_, trace) = solve_with_trace("L70\nR250\n", &config);
+        assert_eq!(
+            trace_to_rotations(&trace, config.start, config.size),
+            Ok(vec![(Right, 30), (Right, 50)])
+        );
+        assert_eq!(trace_to_rotations(&[], 0, 0), Err(ConfigError::EmptyDial));
+    }
+
+    #[test]
+    fn counts_landings_on_a_non_zero_target() {
+        let config = DialConfig {
+            target: 7,
+            ..DialConfig::default()
+        };
+
+        // 50 -> 10 stays above 7, 10 -> 95 sweeps past 7, 95 -> 7 wraps and stops on it.
+        let result = solve_puzzle("L40\nL15\nR12\n", &config);
+        assert_eq!(result.zero_count, 2);
+        assert_eq!(result.final_position, 7);
+        assert_eq!(solve_puzzle_part1("L40\nL15\nR12\n", &config).zero_count, 1);
+
+        assert_eq!(apply_rotation_counting(7, 0, Right, 207, 100), (7, 3));
+        assert_eq!(apply_rotation_counting(7, 7, Left, 100, 100), (7, 1));
+    }
+
+    #[test]
+    fn parse_rotation_accepts_valid_rotations() {
+        assert_eq!(parse_rotation("R10"), Ok((Right, 10)));
+        assert_eq!(parse_rotation("L100"), Ok((Left, 100)));
+    }
+
+    #[test]
+    fn directions_convert_from_either_case() {
+        for (c, direction) in [('L', Left), ('l', Left), ('R', Right), ('r', Right)] {
+            assert_eq!(Direction::try_from(c), Ok(direction));
+        }
+        for c in ['U', 'x', '+', ' ', 'é'] {
+            assert_eq!(Direction::try_from(c), Err(c));
+        }
+
+        assert_eq!((Left.sign(), Right.sign()), (-1, 1));
+        assert_eq!(char::from(Left), 'L');
+        assert_eq!(Right.to_string(), "R");
+    }
+
+    #[test]
+    fn parsed_rotations_solve_like_text() {
+        let input = crate::generator::generate(2_000, 143);
+        let parsed: Vec<_> = rotations(&input).map(Result::unwrap).collect();
+
+        for config in [
+            DialConfig::default(),
+            DialConfig {
+                clockwise_is_right: false,
+                ..DialConfig::default()
+            },
+        ] {
+            assert_eq!(
+                solve_rotations(&parsed, &config),
+                solve_puzzle(&input, &config)
+            );
+        }
+
+        // Rotations that failed to parse elsewhere are warnings, as in text input.
+        let invalid = ParseError::InvalidDirection {
+            line: "U5".to_string(),
+            found: 'U',
+            offset: 0,
+        };
+        let result = solve_parsed(
+            [Ok((Right, 5)), Err(invalid.clone())],
+            &DialConfig::default(),
+        );
+        assert_eq!(result.final_position, 55);
+        assert_eq!(result.lines_skipped, 1);
+        assert_eq!(result.warnings, [invalid]);
+    }
+
+    #[test]
+    fn parse_rotation_rejects_short_lines() {
+        let error = parse_rotation("R").unwrap_err();
+        assert!(matches!(error, ParseError::TooShort { .. }));
+        assert_eq!(error.to_string(), "Line too short: 'R'");
+    }
+
+    #[test]
+    fn parse_rotation_rejects_unknown_directions() {
+        let error = parse_rotation("X5").unwrap_err();
+        assert!(matches!(
+            error,
+            ParseError::InvalidDirection { found: 'X', .. }
+        ));
+        assert_eq!(error.offset(), Some(0));
+        assert_eq!(error.to_string(), "Invalid direction at column 1 in 'X5'");
+    }
+
+    #[test]
+    fn parse_rotation_rejects_non_numeric_distances() {
+        let error = parse_rotation("Rabc").unwrap_err();
+        assert!(matches!(error, ParseError::InvalidNumber { .. }));
+        assert_eq!(error.offset(), Some(1));
+        assert_eq!(
+            error.to_string(),
+            "Invalid number at column 2 in 'Rabc': invalid digit found in string"
+        );
+        assert_eq!(parse_rotation("R").unwrap_err().offset(), None);
+
+        for (line, offset) in [("R+5", 1), ("-+5", 1), ("R0x+5", 3), ("L+0", 1)] {
+            let error = parse_rotation(line).unwrap_err();
+            assert!(
+                matches!(error, ParseError::UnexpectedSign { .. }),
+                "{}",
+                line
+            );
+            assert_eq!(error.offset(), Some(offset), "{}", line);
+        }
+        assert_eq!(
+            parse_rotation("R0x+5").unwrap_err().to_string(),
+            "Unexpected sign at column 4 in 'R0x+5'"
+        );
+    }
+
+    #[test]
+    fn empty_and_blank_inputs_apply_no_rotations() {
+        for input in ["", "  \n\t\n   \n"] {
+            let result = solve_puzzle(input, &DialConfig::default());
+            assert_eq!(result.rotations_applied, 0);
+            assert_eq!(result.zero_count, 0);
+            assert!(result.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn passes_and_stops_are_reported_separately() {
+        // R240 from 50 passes 0 twice and stops on 90; L90 then stops on 0.
+        let result = solve_puzzle("R240\nL90\n", &DialConfig::default());
+
+        assert_eq!(result.passes_through_zero, 3);
+        assert_eq!(result.stops_on_zero, 1);
+        assert_eq!(result.zero_count, result.passes_through_zero);
+
+        let part1 = solve_puzzle_part1("R240\nL90\n", &DialConfig::default());
+        assert_eq!(part1.zero_count, part1.stops_on_zero);
+        assert_eq!(part1.passes_through_zero, 3);
+    }
+
+    #[test]
+    fn zero_distance_rotations_are_no_ops() {
+        assert_eq!(apply_rotation_counting(0, 0, Right, 0, 100), (0, 0));
+
+        let config = DialConfig {
+            start: 0,
+            ..DialConfig::default()
+        };
+        for result in [
+            solve_puzzle("R0\nL0\nR0\n", &config),
+            solve_puzzle_part1("R0\nL0\nR0\n", &config),
+        ] {
+            assert_eq!(result.zero_count, 0);
+            assert_eq!(result.rotations_applied, 0);
+            assert_eq!(result.final_position, 0);
+            assert!(result.warnings.is_empty());
+        }
+    }
+
+    #[test]
+    fn negative_distances_are_rejected() {
+        assert_eq!(parse_rotation("R5"), Ok((Right, 5)));
+
+        let error = parse_rotation("R-5").unwrap_err();
+        assert!(matches!(error, ParseError::NegativeDistance { .. }));
+        assert_eq!(error.to_string(), "Negative distance at column 2 in 'R-5'");
+
+        assert!(matches!(
+            parse_rotation("+-5"),
+            Err(ParseError::NegativeDistance { .. })
+        ));
+    }
+
+    #[test]
+    fn reader_and_string_solvers_agree() {
+        let input = "L68 L30\r\n\n# mid\nR48\nX5\nL5\nR60\nL55\nL1\nL99\nR14\nL82";
+        let config = DialConfig::default();
+
+        let streamed = solve_from_reader(io::Cursor::new(input), &config).unwrap();
+        assert_eq!(streamed, solve_puzzle(input, &config));
+        assert_eq!(streamed.zero_count, 6);
+    }
+
+    #[test]
+    fn merged_results_match_concatenated_input() {
+        let first = "L68\nL30\nR48\nL5\nX1\n";
+        let second = "R60\nL55\nL1\nL99\nR14\nL82\n";
+        let config = DialConfig::default();
+
+        let mut combined = solve_puzzle(first, &config);
+        let carried = DialConfig {
+            start: combined.final_position,
+            ..config
+        };
+        combined.merge(solve_puzzle(second, &carried));
+
+        assert_eq!(
+            combined,
+            solve_puzzle(&format!("{}{}", first, second), &config)
+        );
+        assert_eq!(combined.zero_count, 6);
+    }
+
+    #[test]
+    fn check_reports_bad_lines_with_line_numbers() {
+        let check = check_input("R10\n\n# note\nX5\nL5 Rabc\nR1 L2\n");
+
+        assert_eq!(check.valid_lines, 2);
+        assert_eq!(check.invalid_lines, 2);
+        assert_eq!(check.errors.len(), 2);
+        assert_eq!(check.errors[0].0, 4);
+        assert_eq!(check.errors[1].0, 5);
+        assert!(matches!(
+            check.errors[1].1,
+            ParseError::InvalidNumber { .. }
+        ));
+    }
+
+    #[test]
+    fn skip_reasons_tell_blank_comment_and_invalid_lines_apart() {
+        let reasons = skip_reasons("R10\n\n   # note\nX5\nL5 # fine\n");
+
+        assert!(matches!(
+            reasons.as_slice(),
+            [
+                (2, SkipReason::Blank),
+                (3, SkipReason::Comment),
+                (4, SkipReason::Invalid(ParseError::InvalidDirection { .. }))
+            ]
+        ));
+        assert!(skip_reasons("R10\nL5\n").is_empty());
+    }
+
+    #[test]
+    fn start_must_lie_on_the_dial() {
+        let config = DialConfig::default();
+        let at = |start| DialConfig { start, ..config };
+
+        assert_eq!(at(0).validate(), Ok(()));
+        assert_eq!(at(99).validate(), Ok(()));
+        assert_eq!(
+            at(100).validate(),
+            Err(ConfigError::StartOutOfRange {
+                start: 100,
+                size: 100
+            })
+        );
+        assert!(at(-1).validate().is_err());
+
+        assert_eq!(solve_puzzle("L1\n", &at(0)).zero_count, 0);
+        assert_eq!(solve_puzzle("R1\n", &at(0)).final_position, 1);
+        assert_eq!(solve_puzzle("R1\n", &at(99)).zero_count, 1);
+        assert_eq!(solve_puzzle("L1\n", &at(99)).final_position, 98);
+    }
+
+    #[test]
+    fn rotations_iterator_yields_one_result_per_token() {
+        let parsed: Vec<_> = rotations("R10 L5\n\n# skip\nX3\nR7\n").collect();
+
+        assert_eq!(parsed.len(), 4);
+        assert_eq!(parsed[0], Ok((Right, 10)));
+        assert!(parsed[2].is_err());
+
+        let clockwise: i64 = rotations(EXAMPLE)
+            .filter_map(Result::ok)
+            .filter(|&(direction, _)| direction == Right)
+            .map(|(_, distance)| distance)
+            .sum();
+        assert_eq!(clockwise, 48 + 60 + 14);
+    }
+
+    #[test]
+    fn fast_and_slow_rotation_agree_on_random_and_edge_inputs() {
+        let mut rng = XorShift(0x00C0_FFEE);
+        let mut cases = Vec::new();
+
+        for size in [1, 2, 3, 7, 100, 360] {
+            for position in [0, size - 1, size / 2] {
+                for distance in [0, 1, size as i64 - 1, size as i64, size as i64 + 1, 10_000] {
+                    for direction in [Left, Right] {
+                        cases.push((size, position, direction, distance, 0));
+                    }
+                }
+            }
+        }
+        for _ in 0..20_000 {
+            let size = 1 + rng.below(500) as i32;
+            cases.push((
+                size,
+                rng.below(size as u64) as i32,
+                if rng.below(2) == 0 { Left } else { Right },
+                rng.below(10_001) as i64,
+                rng.below(size as u64) as i32,
+            ));
+        }
+
+        for (size, position, direction, distance, target) in cases {
+            assert_eq!(
+                apply_rotation_counting(target, position, direction, distance, size),
+                apply_rotation_step_by_step_to(target, position, direction, distance, size),
+                "{}{} from {} towards {} on a {}-dial",
+                direction,
+                distance,
+                position,
+                target,
+                size
+            );
+        }
+    }
+
+    #[test]
+    fn crlf_line_endings_match_lf() {
+        let crlf = EXAMPLE.replace('\n', "\r\n") + "\r\n  \r\n";
+        let config = DialConfig::default();
+
+        let result = solve_puzzle(&crlf, &config);
+        assert_eq!(result, solve_puzzle(EXAMPLE, &config));
+        assert!(result.warnings.is_empty());
+
+        let streamed = solve_from_reader(io::Cursor::new(&crlf), &config).unwrap();
+        assert_eq!(streamed, result);
+        assert!(check_input("\r\nR5\r\n\r").errors.is_empty());
+    }
+
+    #[test]
+    fn zero_count_grows_past_u32_max() {
+        let distance = 100 * (u64::from(u32::MAX) + 10);
+        let input = format!("R{}\nL{}\n", distance, distance);
+
+        let result = solve_puzzle(&input, &DialConfig::default());
+
+        assert_eq!(result.zero_count, 2 * (u64::from(u32::MAX) + 10));
+        assert!(result.zero_count > u64::from(u32::MAX));
+
+        let huge = format!("R{}\n", i64::MAX).repeat(300);
+        assert_eq!(
+            solve_puzzle(&huge, &DialConfig::default()).zero_count,
+            u64::MAX
+        );
+    }
+
+    #[test]
+    fn first_zero_line_finds_the_first_landing() {
+        let config = DialConfig::default();
+
+        assert_eq!(first_zero_line("R50\nL10\n", &config), Some(1));
+        assert_eq!(first_zero_line(EXAMPLE, &config), Some(3));
+        assert_eq!(first_zero_line("R10\nL5\nR99\n", &config), None);
+    }
+
+    #[test]
+    fn first_zero_line_stops_reading_after_the_hit() {
+        let input = "L50\nnot a rotation\n";
+        assert_eq!(first_zero_line(input, &DialConfig::default()), Some(1));
+    }
+
+    #[test]
+    fn warnings_can_be_captured_in_a_buffer() {
+        let mut warnings = Vec::new();
+
+        let result = solve_puzzle_to("R5\nX7\nL\n", &DialConfig::default(), &mut warnings).unwrap();
+
+        assert_eq!(result.rotations_applied, 1);
+        assert_eq!(
+            String::from_utf8(warnings).unwrap(),
+            "Warning: Invalid rotation 'X7': Invalid direction at column 1 in 'X7'\n\
+             Warning: Invalid rotation 'L': Line too short: 'L'\n"
+        );
+    }
+
+    #[test]
+    fn exact_revolutions_return_to_the_start() {
+        for (position, revolutions) in [(0, 1), (50, 1), (99, 3), (0, 1_000_000)] {
+            let distance = 100 * revolutions;
+            for direction in [Left, Right] {
+                assert_eq!(
+                    apply_rotation_counting(0, position, direction, distance, 100),
+                    (position, revolutions as u64),
+                    "{}{} from {}",
+                    direction,
+                    distance,
+                    position
+                );
+            }
+        }
+
+        let result = solve_puzzle("R100\nL300\n", &DialConfig::default());
+        assert_eq!(result.final_position, 50);
+        assert_eq!(result.zero_count, 4);
+        assert_eq!(result.stops_on_zero, 0);
+    }
+
+    #[test]
+    fn final_position_matches_the_full_solver() {
+        let config = DialConfig::default();
+        let inputs = [
+            EXAMPLE.to_string(),
+            "R250\nR975\nL3\n".to_string(),
+            "L1000\nL1001\nbad\nR7\n".to_string(),
+            format!("R{}\nR{}\nL5\n", i64::MAX, i64::MAX),
+            crate::generator::generate(2_000, 99),
+        ];
+
+        for input in &inputs {
+            assert_eq!(
+                final_position(input, &config),
+                solve_puzzle(input, &config).final_position
+            );
+        }
+        assert_eq!(final_position("R250\nR975\nL3\n", &config), 72);
+    }
+
+    #[test]
+    fn underscores_separate_digits() {
+        assert_eq!(parse_rotation("R1_000"), Ok((Right, 1000)));
+        assert_eq!(parse_rotation("R1000"), Ok((Right, 1000)));
+        assert_eq!(parse_rotation("L1_000_000"), Ok((Left, 1_000_000)));
+
+        for token in ["R1__", "R_5", "R5_", "R1__0", "R_", "R-_1"] {
+            assert!(
+                matches!(parse_rotation(token), Err(ParseError::InvalidNumber { .. })),
+                "{}",
+                token
+            );
+        }
+    }
+
+    #[test]
+    fn repeated_warnings_are_collapsed() {
+        let input = "X1\n".repeat(1_000) + "R50\nL\nX1\n";
+        let mut warnings = Vec::new();
+
+        let result = solve_puzzle_to(&input, &DialConfig::default(), &mut warnings).unwrap();
+
+        assert_eq!(result.zero_count, 1);
+        assert_eq!(result.lines_skipped, 1_002);
+        assert_eq!(
+            String::from_utf8(warnings).unwrap(),
+            "Warning: Invalid rotation 'X1': Invalid direction at column 1 in 'X1' (x 1001)\n\
+             Warning: Invalid rotation 'L': Line too short: 'L'\n"
+        );
+    }
+
+    #[test]
+    fn flipping_the_convention_swaps_left_and_right() {
+        let config = DialConfig::default();
+        let flipped = DialConfig {
+            clockwise_is_right: false,
+            ..config
+        };
+        let input = "L68\nR30\nL7\n";
+        let swapped = "R68\nL30\nR7\n";
+
+        assert_eq!(
+            solve_puzzle(input, &flipped),
+            solve_puzzle(swapped, &config)
+        );
+        assert_eq!(solve_puzzle(input, &flipped).final_position, 95);
+        assert_eq!(final_position(input, &flipped), 95);
+        assert_eq!(solve_puzzle(input, &config).final_position, 5);
+    }
+
+    #[test]
+    fn hex_distances_match_decimal() {
+        assert_eq!(parse_rotation("R0x10"), parse_rotation("R16"));
+        assert_eq!(parse_rotation("L0X1f"), Ok((Left, 31)));
+        assert_eq!(parse_rotation("R0xFF_FF"), Ok((Right, 65_535)));
+        assert_eq!(
+            solve_puzzle("R0x10\nL0x64\n", &DialConfig::default()),
+            solve_puzzle("R16\nL100\n", &DialConfig::default())
+        );
+
+        for token in ["R0xZZ", "R0x", "R0xG1", "R0x_1"] {
+            assert!(
+                matches!(parse_rotation(token), Err(ParseError::InvalidNumber { .. })),
+                "{}",
+                token
+            );
+        }
+    }
+
+    #[test]
+    fn positions_stay_on_the_dial_for_huge_left_turns() {
+        let mut rng = XorShift(0x64);
+
+        for _ in 0..5_000 {
+            let size = 1 + rng.below(1_000) as u32;
+            let config = DialConfig {
+                size,
+                start: rng.below(u64::from(size)) as i32,
+                ..DialConfig::default()
+            };
+            let distance = i64::MAX - rng.below(1 << 40) as i64;
+
+            let (position, _) =
+                apply_rotation_with_zero_count(config.start, Left, distance, &config);
+            assert!(
+                (0..size as i32).contains(&position),
+                "{} on {}",
+                position,
+                size
+            );
+        }
+
+        let input = format!("L{}\n", i64::MAX).repeat(50) + &format!("L{}\n", u32::MAX);
+        let (result, trace) = solve_with_trace(&input, &DialConfig::default());
+        assert!(trace.iter().all(|position| (0..100).contains(position)));
+        assert!(result.warnings.is_empty());
+
+        let off_dial = ParseError::PositionOffDial {
+            line: "L5".to_string(),
+            position: -3,
+            size: 100,
+        };
+        assert_eq!(
+            format_warning(&off_dial),
+            "Warning: Invalid rotation 'L5': Position -3 left the dial 0..100; wrapped it back"
+        );
+    }
+
+    #[test]
+    fn coalescing_keeps_part_two_answer() {
+        assert_eq!(
+            coalesce(&[
+                (Right, 5),
+                (Right, 3),
+                (Left, 0),
+                (Right, 2),
+                (Left, 4),
+                (Left, 1)
+            ]),
+            vec![(Right, 10), (Left, 5)]
+        );
+        assert_eq!(
+            coalesce(&[(Right, i64::MAX), (Right, 1)]),
+            vec![(Right, i64::MAX), (Right, 1)]
+        );
+
+        let config = DialConfig::default();
+        for input in [EXAMPLE.to_string(), crate::generator::generate(5_000, 65)] {
+            let raw: Vec<_> = rotations(&input).map(Result::unwrap).collect();
+            let coalesced: String = coalesce(&raw)
+                .iter()
+                .map(|(direction, distance)| format!("{}{}\n", direction, distance))
+                .collect();
+
+            let expected = solve_puzzle(&input, &config);
+            let actual = solve_puzzle(&coalesced, &config);
+            assert_eq!(actual.zero_count, expected.zero_count);
+            assert_eq!(actual.final_position, expected.final_position);
+            assert_eq!(actual.clicks, expected.clicks);
+        }
+    }
+
+    #[test]
+    fn minimize_drops_padding_rotations() {
+        let config = DialConfig::default();
+
+        assert_eq!(
+            minimize("L10\nR10\nR50 # here\nR20\nL10\n", &config, 1),
+            Some(vec![(Right, 50)])
+        );
+
+        let shrunk = minimize(EXAMPLE, &config, 6).unwrap();
+        assert!(shrunk.len() < 10);
+        assert_eq!(solve_rotations(&shrunk, &config).zero_count, 6);
+
+        assert_eq!(minimize(EXAMPLE, &config, 7), None);
+        assert_eq!(minimize("", &config, 0), Some(vec![]));
+    }
+
+    #[test]
+    fn full_revolutions_follow_net_travel() {
+        let config = DialConfig::default();
+
+        assert_eq!(solve_puzzle("R200\nR150\n", &config).full_revolutions, 3);
+        assert_eq!(solve_puzzle("L100\nL250\n", &config).full_revolutions, 3);
+        assert_eq!(solve_puzzle("R300\nL300\n", &config).full_revolutions, 0);
+        assert_eq!(solve_puzzle("R99\n", &config).full_revolutions, 0);
+    }
+
+    #[test]
+    fn distances_are_totalled_per_direction() {
+        let result = solve_puzzle("L68\nR30\nL250 R0\nR5\n", &DialConfig::default());
+
+        assert_eq!(result.left_distance, 318);
+        assert_eq!(result.right_distance, 35);
+        assert_eq!(result.left_distance + result.right_distance, result.clicks);
+    }
+
+    #[test]
+    fn final_position_survives_travel_beyond_i64() {
+        let config = DialConfig::default();
+        let input = format!("R{}\n", i64::MAX).repeat(1_000);
+
+        // 1000 * (i64::MAX % 100) = 1000 * 7, which is a whole number of turns.
+        assert_eq!(final_position(&input, &config), 50);
+        assert_eq!(
+            final_position(&input, &config),
+            solve_puzzle(&input, &config).final_position
+        );
+
+        let back = format!("{}L{}\nL1\n", input, i64::MAX);
+        assert_eq!(final_position(&back, &config), 42);
+    }
+
+    #[test]
+    fn custom_parsers_plug_into_the_solver() {
+        let words = |line: &str| match line.split_whitespace().collect::<Vec<_>>()[..] {
+            ["turn", "left", n] => parse_rotation(&format!("L{}", n)),
+            ["turn", "right", n] => parse_rotation(&format!("R{}", n)),
+            _ => Err(ParseError::InvalidDirective {
+                line: line.to_string(),
+                reason: "expected 'turn left|right <n>'".to_string(),
+            }),
+        };
+        let input = "turn left 68\n# comment\nturn left 30\n\nturn right 48\nspin 5\n";
+
+        let result = solve_puzzle_with(input, &DialConfig::default(), words);
+
+        let expected = solve_puzzle("L68\nL30\nR48\nX5\n", &DialConfig::default());
+        assert_eq!(result.zero_count, expected.zero_count);
+        assert_eq!(result.final_position, 0);
+        assert_eq!(result.lines_skipped, 1);
+        assert_eq!(
+            solve_puzzle_with(EXAMPLE, &DialConfig::default(), parse_rotation),
+            solve_puzzle(EXAMPLE, &DialConfig::default())
+        );
+    }
+
+    #[test]
+    fn filtered_zeros_still_track_position() {
+        let config = DialConfig::default();
+
+        let rightward = solve_filtered(EXAMPLE, &config, |direction, _| direction == Right);
+        let leftward = solve_filtered(EXAMPLE, &config, |direction, _| direction == Left);
+        let everything = solve_puzzle(EXAMPLE, &config);
+
+        assert_eq!(rightward.zero_count, 2);
+        assert_eq!(
+            rightward.zero_count + leftward.zero_count,
+            everything.zero_count
+        );
+        assert_eq!(rightward.final_position, everything.final_position);
+        assert_eq!(rightward.rotations_applied, everything.rotations_applied);
+        assert_eq!(solve_filtered(EXAMPLE, &config, |_, _| true), everything);
+    }
+
+    #[test]
+    fn solving_parsed_rotations_is_deterministic() {
+        let config = DialConfig::default();
+        let parsed = rotations(EXAMPLE).map(Result::unwrap).collect::<Vec<_>>();
+
+        let once = solve_rotations(&parsed, &config);
+        assert_eq!(once, solve_rotations(&parsed, &config));
+        assert_eq!(once, solve_puzzle(EXAMPLE, &config));
+    }
+
+    #[test]
+    fn rotation_order_matters() {
+        let config = DialConfig::default();
+        let mut rng = XorShift(78);
+        let mut parsed = rotations(EXAMPLE).map(Result::unwrap).collect::<Vec<_>>();
+
+        let forward = solve_rotations(&parsed, &config);
+        parsed.reverse();
+        assert_ne!(
+            solve_rotations(&parsed, &config).zero_count,
+            forward.zero_count
+        );
+
+        // Shuffling keeps the net travel, so the final position never changes.
+        for _ in 0..20 {
+            for i in (1..parsed.len()).rev() {
+                parsed.swap(i, rng.below(i as u64 + 1) as usize);
+            }
+            let shuffled = solve_rotations(&parsed, &config);
+            assert_eq!(shuffled.final_position, forward.final_position);
+            assert_eq!(shuffled.clicks, forward.clicks);
+        }
+    }
+
+    #[test]
+    fn reversed_input_returns_to_the_start() {
+        let input = crate::generator::generate(500, 86);
+
+        for config in [
+            DialConfig::default(),
+            DialConfig {
+                size: 7,
+                start: 3,
+                clockwise_is_right: false,
+                ..DialConfig::default()
+            },
+        ] {
+            let forward = solve_puzzle(&input, &config);
+            let reversed = solve_reversed(&input, &config);
+
+            assert_eq!(reversed.final_position, config.start);
+            assert_eq!(reversed.rotations_applied, forward.rotations_applied);
+            assert_eq!(reversed.clicks, forward.clicks);
+            assert_eq!(reversed.net_offset, -forward.net_offset);
+        }
+    }
+
+    #[test]
+    fn closing_rotation_returns_to_the_start() {
+        let input = crate::generator::generate(200, 87);
+        let mirrored = DialConfig {
+            size: 7,
+            start: 3,
+            mirrored: true,
+            ..DialConfig::default()
+        };
+
+        for (input, config) in [
+            (input.as_str(), DialConfig::default()),
+            (input.as_str(), mirrored),
+            ("R50\n", DialConfig::default()),
+            ("L100\n", DialConfig::default()),
+        ] {
+            let (direction, distance) = closing_rotation(input, &config).unwrap();
+            assert!(distance * 2 <= i64::from(config.size));
+
+            let closed = format!("{}{}{}\n", input, direction, distance);
+            assert_eq!(final_position(&closed, &config), config.start);
+        }
+
+        let config = DialConfig::default();
+        assert_eq!(closing_rotation("R50\n", &config), Ok((Right, 50)));
+        assert_eq!(closing_rotation("R30\n", &config), Ok((Left, 30)));
+        assert_eq!(closing_rotation("L30 R30\n", &config), Ok((Right, 0)));
+
+        let empty = DialConfig {
+            size: 0,
+            start: 0,
+            ..DialConfig::default()
+        };
+        assert_eq!(
+            closing_rotation("R5\n", &empty),
+            Err(ConfigError::EmptyDial)
+        );
+    }
+
+    #[test]
+    fn rotation_slices_solve_like_text() {
+        let config = DialConfig::default();
+        let slice = [(Left, 68), (Left, 30), (Right, 48), (Left, 5), (Right, 60)];
+        assert_eq!(
+            solve_rotations(&slice, &config),
+            solve_puzzle("L68\nL30\nR48\nL5\nR60\n", &config)
+        );
+
+        let input = crate::generator::generate(1_000, 128);
+        let parsed = rotations(&input).map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(
+            solve_rotations(&parsed, &config),
+            solve_puzzle(&input, &config)
+        );
+    }
+
+    #[test]
+    fn targets_are_reached_the_short_way() {
+        let config = DialConfig::default();
+
+        // R40, R20 through 0, R50 on the tie, then R40 onto 0.
+        let result = solve_targets("90\n10\n# halfway\n60\n0\n", &config);
+        assert_eq!(result.zero_count, 2);
+        assert_eq!(result.final_position, 0);
+        assert_eq!((result.right_distance, result.left_distance), (150, 0));
+
+        let back = solve_targets("20\n70\n", &config);
+        assert_eq!((back.left_distance, back.right_distance), (30, 50));
+        assert_eq!(back.zero_count, 0);
+
+        let invalid = solve_targets("50\n100\nabc\n-1\n", &config);
+        assert_eq!(invalid.rotations_applied, 0);
+        assert_eq!(invalid.lines_skipped, 3);
+    }
+
+    #[test]
+    fn first_divergence_finds_the_mistyped_line() {
+        let config = DialConfig::default();
+        let typo = EXAMPLE.replacen("L55", "L56", 1);
+
+        assert_eq!(first_divergence(EXAMPLE, &typo, &config), Some(6));
+        assert_eq!(first_divergence(EXAMPLE, EXAMPLE, &config), None);
+        // Same positions, different spelling.
+        assert_eq!(first_divergence("R50\nL5\n", "L50\nL105\n", &config), None);
+        assert_eq!(first_divergence("R10\n", "R10\nL3\n", &config), Some(2));
+        assert_eq!(
+            first_divergence("R10\nSET 0\n", "R10\nR0\n", &config),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn clicks_to_zero_around_zero() {
+        assert_eq!(clicks_to_zero(99, Right, 100), Ok(1));
+        assert_eq!(clicks_to_zero(1, Right, 100), Ok(99));
+        assert_eq!(clicks_to_zero(1, Left, 100), Ok(1));
+        assert_eq!(clicks_to_zero(99, Left, 100), Ok(99));
+        assert_eq!(clicks_to_zero(0, Right, 100), Ok(100));
+        assert_eq!(clicks_to_zero(0, Left, 100), Ok(100));
+        assert_eq!(clicks_to_zero(0, Left, 1), Ok(1));
+        assert_eq!(clicks_to_zero(0, Right, 0), Err(ConfigError::EmptyDial));
+
+        for (position, direction) in [(37, Right), (37, Left), (0, Right)] {
+            let distance = clicks_to_zero(position, direction, 100).unwrap();
+            let (to, zeros) = apply_rotation_with_zero_count(
+                position,
+                direction,
+                i64::from(distance),
+                &DialConfig::default(),
+            );
+            assert_eq!((to, zeros), (0, 1));
+        }
+    }
+
+    #[test]
+    fn rotation_periods_follow_the_gcd() {
+        assert_eq!(rotation_period(25, 100), Ok(4));
+        assert_eq!(rotation_period(50, 100), Ok(2));
+        assert_eq!(rotation_period(1, 100), Ok(100));
+        assert_eq!(rotation_period(-30, 100), Ok(10));
+        assert_eq!(rotation_period(0, 100), Ok(1));
+        assert_eq!(rotation_period(i64::MAX, 7), Ok(1));
+        assert_eq!(rotation_period(i64::MIN, 7), Ok(7));
+        assert_eq!(rotation_period(7, u32::MAX), Ok(u32::MAX));
+        assert_eq!(rotation_period(0, 0), Err(ConfigError::EmptyDial));
+        assert_eq!(rotation_period(35, 0), Err(ConfigError::EmptyDial));
+
+        let config = DialConfig::default();
+        let period = rotation_period(35, 100).unwrap();
+        let input = "R35\n".repeat(period as usize);
+        assert_eq!(final_position(&input, &config), config.start);
+    }
+
+    #[test]
+    fn all_conventions_cover_both_directions_and_parts() {
+        let config = DialConfig {
+            start: 10,
+            ..DialConfig::default()
+        };
+        let input = "L10\nR95\nR5\n";
+        let results = solve_all_conventions(input, &config);
+
+        // Clockwise `R`: 0, 95, 0. Clockwise `L`: 20, 25 via 0, 20.
+        let zeros = results
+            .each_ref()
+            .map(|row| row.each_ref().map(|r| r.zero_count));
+        assert_eq!(zeros, [[2, 2], [1, 0]]);
+        assert_eq!(results[0][0].final_position, 0);
+        assert_eq!(results[1][1].final_position, 20);
+        assert_eq!(results[0][0], solve_puzzle(input, &config));
+    }
+
+    #[test]
+    fn multi_target_counts_every_special_position() {
+        let config = DialConfig::default();
+        let input = crate::generator::generate(300, 89);
+
+        assert_eq!(
+            solve_multi_target(&input, &config, &[0]),
+            solve_puzzle(&input, &config)
+        );
+
+        let result = solve_multi_target("R30\nL260\n", &config, &[0, 25, 50, 75, 200]);
+        assert_eq!(result.zero_count, 1 + 8 + 3);
+        assert_eq!(result.stops_on_zero, 0);
+        assert_eq!(
+            solve_multi_target("R25\n", &config, &[0, 75]).stops_on_zero,
+            1
+        );
+        assert_eq!(solve_multi_target("R500\n", &config, &[]).zero_count, 0);
+
+        let resized = "R30\nDIAL 10\nR10\nL23\nDIAL 1000\nR2000\n";
+        assert_eq!(
+            solve_multi_target(resized, &config, &[0]),
+            solve_puzzle(resized, &config)
+        );
+        assert_eq!(
+            solve_multi_target("DIAL 10\nR10\n", &config, &[5, 50]).zero_count,
+            1
+        );
+    }
+
+    #[test]
+    fn a_dead_zone_on_the_target_counts_nothing() {
+        let config = DialConfig::default();
+
+        let dead = solve_with_dead_zones(EXAMPLE, &config, &[0, 13]);
+        assert_eq!(dead.zero_count, 0);
+        assert_eq!(dead.stops_on_zero, 0);
+        assert_eq!(dead.final_position, 32);
+
+        assert_eq!(
+            solve_with_dead_zones(EXAMPLE, &config, &[13, 99]),
+            solve_puzzle(EXAMPLE, &config)
+        );
+    }
+
+    #[test]
+    fn verify_accepts_only_the_right_answer() {
+        let config = DialConfig::default();
+
+        assert!(crate::verify(EXAMPLE, &config, 6));
+        assert!(!crate::verify(EXAMPLE, &config, 3));
+    }
+
+    #[test]
+    fn zero_fraction_is_zeros_per_click() {
+        let config = DialConfig::default();
+
+        let result = solve_puzzle("R50\nL200\n", &config);
+        assert_eq!((result.zero_count, result.clicks), (3, 250));
+        assert_eq!(result.zero_fraction, 0.012);
+
+        let part1 = solve_puzzle_part1("R50\nL200\n", &config);
+        assert_eq!(part1.zero_fraction, 2.0 / 250.0);
+
+        assert_eq!(solve_puzzle("", &config).zero_fraction, 0.0);
+        assert_eq!(solve_puzzle("R0\n", &config).zero_fraction, 0.0);
+    }
+
+    #[test]
+    fn strict_solving_stops_at_the_first_bad_line() {
+        let config = DialConfig::default();
+
+        let (line_number, error) = solve_strict("R10\n\nL5 X3 R1\nQ9\n", &config).unwrap_err();
+        assert_eq!(line_number, 3);
+        assert!(matches!(
+            error,
+            ParseError::InvalidDirection { found: 'X', .. }
+        ));
+
+        // A huge REPEAT body stops at its first bad rotation too.
+        let (line_number, _) =
+            solve_strict("REPEAT 1000000000000 { R1 X1 }\n", &config).unwrap_err();
+        assert_eq!(line_number, 1);
+
+        assert_eq!(
+            solve_strict(EXAMPLE, &config),
+            Ok(solve_puzzle(EXAMPLE, &config))
+        );
+    }
+
+    #[test]
+    fn degree_distances_become_clicks() {
+        let config = DialConfig {
+            degrees: true,
+            ..DialConfig::default()
+        };
+
+        assert_eq!(solve_puzzle("R90\n", &config).final_position, 75);
+        let full = solve_puzzle("R360\n", &config);
+        assert_eq!(
+            (full.final_position, full.zero_count, full.clicks),
+            (50, 1, 100)
+        );
+
+        // 2 degrees is 0.56 clicks and rounds up; 1 degree (0.28) does not move.
+        assert_eq!(config.clicks(2), 1);
+        assert_eq!(config.clicks(9), 3);
+        let tiny = solve_puzzle("R1\nL2\n", &config);
+        assert_eq!((tiny.rotations_applied, tiny.final_position), (1, 49));
+        assert_eq!(final_position("R1\nL2\n", &config), 49);
+    }
+
+    #[test]
+    fn scaled_distances_saturate_instead_of_overflowing() {
+        let config = DialConfig {
+            scale: 3,
+            ..DialConfig::default()
+        };
+
+        let result = solve_puzzle("R10 L5\n", &config);
+        assert_eq!((result.final_position, result.clicks), (65, 45));
+        assert_eq!(config.clicks(i64::MAX), i64::MAX);
+        let degrees = DialConfig {
+            degrees: true,
+            scale: u32::MAX,
+            ..config
+        };
+        assert_eq!(degrees.clicks(i64::MAX), i64::MAX);
+        assert_eq!(final_position("R40\n", &config), 70);
+    }
+
+    #[test]
+    fn starting_on_the_target_counts_only_when_asked() {
+        let config = DialConfig {
+            start: 0,
+            ..DialConfig::default()
+        };
+        let counted = DialConfig {
+            count_initial: true,
+            ..config
+        };
+
+        assert_eq!(solve_puzzle("R5\n", &config).zero_count, 0);
+        assert_eq!(solve_puzzle("R5\n", &counted).zero_count, 1);
+        assert_eq!(solve_puzzle_part1("", &counted).zero_count, 1);
+        assert_eq!(solve_puzzle("L100\n", &counted).zero_count, 2);
+
+        let elsewhere = DialConfig {
+            count_initial: true,
+            ..DialConfig::default()
+        };
+        assert_eq!(solve_puzzle("R5\n", &elsewhere).zero_count, 0);
+    }
+
+    #[test]
+    fn mirrored_numbering_reflects_every_position() {
+        let input = crate::generator::generate(300, 108);
+        let normal = DialConfig {
+            start: 20,
+            target: 7,
+            ..DialConfig::default()
+        };
+        let mirrored = DialConfig {
+            start: normal.mirror(normal.start).unwrap(),
+            target: normal.mirror(normal.target).unwrap(),
+            mirrored: true,
+            ..normal
+        };
+
+        let expected = solve_puzzle(&input, &normal);
+        let result = solve_puzzle(&input, &mirrored);
+        assert_eq!(result.zero_count, expected.zero_count);
+        assert_eq!(result.stops_on_zero, expected.stops_on_zero);
+        assert_eq!(
+            result.final_position,
+            normal.mirror(expected.final_position).unwrap()
+        );
+
+        // Zero is its own mirror image.
+        let at_zero = DialConfig {
+            mirrored: true,
+            ..DialConfig::default()
+        };
+        assert_eq!(solve_puzzle("R50\nL100\n", &at_zero).zero_count, 2);
+        assert_eq!(solve_puzzle("R30\n", &at_zero).final_position, 20);
+
+        let empty = DialConfig {
+            size: 0,
+            start: 0,
+            ..DialConfig::default()
+        };
+        assert_eq!(empty.mirror(0), Err(ConfigError::EmptyDial));
+    }
+
+    #[test]
+    fn repeated_rotations_are_answered_from_the_cache() {
+        let config = DialConfig::default();
+        let input = "R17\n".repeat(300);
+        let mut run = Run::new(&config, Part::Two, |_: &Step| {});
+        run.cache = Some(RotationCache::default());
+
+        for (index, line) in input.lines().enumerate() {
+            run.process_line(index + 1, line);
+        }
+
+        // R17 reaches all 100 positions before it starts from one again.
+        let cache = run.cache.as_ref().unwrap();
+        assert_eq!((cache.len(), cache.hits()), (100, 200));
+        assert_eq!(run.result, solve_puzzle(&input, &config));
+        assert_eq!(solve_puzzle_cached(&input, &config), run.result);
+    }
 }
@@ -0,0 +1,136 @@
+use crate::cli::Cli;
+use day_1::{SolveResult, SolverBuilder};
+use std::io::{self, BufRead, Write};
+
+// Applies each line as soon as it is typed, on the dial the lines before left.
+// `reset` starts over on the configured dial, `pos` shows the position and
+// `quit` (or end of input) prints the answer. `--limit` and `--max-clicks`
+// span the whole session, resets included.
+pub fn repl<R: BufRead, W: Write>(
+    builder: SolverBuilder,
+    cli: &Cli,
+    input: R,
+    mut output: W,
+) -> io::Result<SolveResult> {
+    let mut solver = crate::build(builder);
+    let config = *solver.config();
+    let fresh = || SolveResult {
+        final_position: config.start,
+        dial_size: config.size,
+        ..SolveResult::default()
+    };
+    let mut total = fresh();
+    let (mut rotations_used, mut clicks_used) = (0usize, 0u64);
+
+    for line in input.lines() {
+        match line?.trim() {
+            "quit" => break,
+            "reset" => total = fresh(),
+            "pos" => writeln!(output, "position {}", total.final_position)?,
+            line => {
+                let result = solver.continue_from(&total).run(line);
+                crate::report_warnings(&result.warnings, cli.warn_level, false);
+
+                rotations_used += result.rotations_applied;
+                clicks_used = clicks_used.saturating_add(result.clicks);
+                let mut limited = builder;
+                if let Some(limit) = cli.limit {
+                    limited = limited.limit(limit.saturating_sub(rotations_used));
+                }
+                if let Some(max_clicks) = cli.max_clicks {
+                    limited = limited.max_clicks(max_clicks.saturating_sub(clicks_used));
+                }
+                solver = crate::build(limited);
+                total.merge(result);
+                writeln!(
+                    output,
+                    "position {}, zeros {}",
+                    total.final_position, total.zero_count
+                )?;
+            }
+        }
+    }
+
+    writeln!(output, "Password: {}", total.zero_count)?;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session_with(cli: &Cli, input: &str) -> String {
+        let mut output = Vec::new();
+        let mut builder = SolverBuilder::new();
+        if let Some(limit) = cli.limit {
+            builder = builder.limit(limit);
+        }
+        repl(builder, cli, input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap()
+    }
+
+    fn session(input: &str) -> String {
+        session_with(&Cli::default(), input)
+    }
+
+    #[test]
+    fn rotations_apply_as_they_are_typed() {
+        assert_eq!(
+            session("L68\nL30\npos\n\nR48\n"),
+            "position 82, zeros 1\n\
+             position 52, zeros 1\n\
+             position 52\n\
+             position 52, zeros 1\n\
+             position 0, zeros 2\n\
+             Password: 2\n"
+        );
+    }
+
+    #[test]
+    fn reset_and_quit() {
+        assert_eq!(
+            session("R50\nreset\npos\nquit\nR50\n"),
+            "position 0, zeros 1\nposition 50\nPassword: 0\n"
+        );
+    }
+
+    #[test]
+    fn reset_lines_return_to_the_configured_start() {
+        assert_eq!(
+            session("R10\nRESET\nDIAL 10\nR3\nRESET\n"),
+            "position 60, zeros 0\n\
+             position 50, zeros 0\n\
+             position 5, zeros 0\n\
+             position 8, zeros 0\n\
+             position 5, zeros 0\n\
+             Password: 0\n"
+        );
+    }
+
+    #[test]
+    fn dial_lines_carry_over_to_later_lines() {
+        assert_eq!(
+            session("DIAL 200\nR70\nR1\n"),
+            "position 100, zeros 0\n\
+             position 170, zeros 0\n\
+             position 171, zeros 0\n\
+             Password: 0\n"
+        );
+    }
+
+    #[test]
+    fn limit_spans_the_whole_session() {
+        let cli = Cli {
+            limit: Some(3),
+            ..Cli::default()
+        };
+
+        assert_eq!(
+            session_with(&cli, "R10 R10\nreset\nR10 R10\nR10\n"),
+            "position 70, zeros 0\n\
+             position 60, zeros 0\n\
+             position 60, zeros 0\n\
+             Password: 0\n"
+        );
+    }
+}
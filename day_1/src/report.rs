@@ -0,0 +1,285 @@
+use crate::puzzle_engine::SolveResult;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    // `zeros=6 final=32 rotations=10 skipped=0`, for grepping logs.
+    Kv,
+}
+
+// How the text answer writes the zero count. JSON and kv always use a plain number.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Base {
+    Binary,
+    Octal,
+    #[default]
+    Decimal,
+    Hex,
+}
+
+impl Base {
+    pub fn format(self, count: u64) -> String {
+        match self {
+            Base::Binary => format!("{:#b}", count),
+            Base::Octal => format!("{:#o}", count),
+            Base::Decimal => count.to_string(),
+            Base::Hex => format!("{:#x}", count),
+        }
+    }
+}
+
+pub const RED: &str = "31";
+pub const GREEN: &str = "32";
+
+pub fn paint(text: &str, color: &str) -> String {
+    format!("\x1b[{}m{}\x1b[0m", color, text)
+}
+
+pub fn render(result: &SolveResult, format: Format, base: Base) -> String {
+    match format {
+        Format::Text => format!("Password: {}", base.format(result.zero_count)),
+        Format::Json => to_json(result),
+        Format::Kv => format!(
+            "zeros={} final={} rotations={} skipped={}",
+            result.zero_count,
+            result.final_position,
+            result.rotations_applied,
+            result.lines_skipped
+        ),
+    }
+}
+
+pub fn to_json(result: &SolveResult) -> String {
+    format!(
+        "{{\"zero_count\": {}, \"final_position\": {}, \"rotations\": {}}}",
+        result.zero_count, result.final_position, result.rotations_applied
+    )
+}
+
+// Everything `--stats-json` reports, on one line. The histogram and visited
+// count come from the analysis pass, which plain runs skip.
+pub fn stats_json(result: &SolveResult, histogram: &[u64], visited_positions: usize) -> String {
+    let histogram: Vec<String> = histogram.iter().map(u64::to_string).collect();
+
+    format!(
+        "{{\"zero_count\": {}, \"passes_through_zero\": {}, \"stops_on_zero\": {}, \
+         \"final_position\": {}, \"rotations\": {}, \"clicks\": {}, \"zero_fraction\": {}, \
+         \"left_distance\": {}, \"right_distance\": {}, \"net_offset\": {}, \
+         \"full_revolutions\": {}, \"lines_skipped\": {}, \"visited_positions\": {}, \
+         \"histogram\": [{}]}}",
+        result.zero_count,
+        result.passes_through_zero,
+        result.stops_on_zero,
+        result.final_position,
+        result.rotations_applied,
+        result.clicks,
+        result.zero_fraction,
+        result.left_distance,
+        result.right_distance,
+        result.net_offset,
+        result.full_revolutions,
+        result.lines_skipped,
+        visited_positions,
+        histogram.join(", ")
+    )
+}
+
+pub fn render_summary(result: &SolveResult) -> String {
+    let fields = [
+        ("Rotations", result.rotations_applied.to_string()),
+        ("Clicks", result.clicks.to_string()),
+        ("Zeros", result.zero_count.to_string()),
+        ("Final position", result.final_position.to_string()),
+        ("Skipped lines", result.lines_skipped.to_string()),
+    ];
+    let width = fields.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+
+    fields
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                "{:<width$}  {}",
+                format!("{}:", name),
+                value,
+                width = width + 1
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Dials up to this size are drawn whole; larger ones only around the marker.
+pub const FULL_DIAL_LIMIT: u32 = 20;
+const NEIGHBORHOOD: i64 = 5;
+
+pub fn render_dial(position: i32, size: u32) -> String {
+    let mark = |p: i64| {
+        if p == i64::from(position) {
+            format!("[{}]", p)
+        } else {
+            p.to_string()
+        }
+    };
+
+    if size <= FULL_DIAL_LIMIT {
+        return (0..i64::from(size)).map(mark).collect::<Vec<_>>().join(" ");
+    }
+
+    let size = i64::from(size);
+    let around: Vec<String> = (-NEIGHBORHOOD..=NEIGHBORHOOD)
+        .map(|offset| mark((i64::from(position) + offset).rem_euclid(size)))
+        .collect();
+    format!("... {} ...", around.join(" "))
+}
+
+// 64-bit FNV-1a over the answer fields as fixed-width little-endian bytes, so
+// the value is the same on every machine and every run.
+pub fn run_fingerprint(result: &SolveResult) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+
+    let bytes = [
+        result.zero_count.to_le_bytes(),
+        i64::from(result.final_position).to_le_bytes(),
+        (result.rotations_applied as u64).to_le_bytes(),
+    ];
+
+    bytes.iter().flatten().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(PRIME)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> SolveResult {
+        SolveResult {
+            zero_count: 6,
+            final_position: 32,
+            rotations_applied: 10,
+            clicks: 562,
+            ..SolveResult::default()
+        }
+    }
+
+    #[test]
+    fn text_format_keeps_password_line() {
+        assert_eq!(
+            render(&sample(), Format::Text, Base::Decimal),
+            "Password: 6"
+        );
+    }
+
+    #[test]
+    fn answers_can_use_other_bases() {
+        let result = SolveResult {
+            zero_count: 1092,
+            ..sample()
+        };
+
+        assert_eq!(render(&result, Format::Text, Base::Hex), "Password: 0x444");
+        assert_eq!(
+            render(&result, Format::Text, Base::Binary),
+            "Password: 0b10001000100"
+        );
+        assert_eq!(
+            render(&result, Format::Text, Base::Octal),
+            "Password: 0o2104"
+        );
+        assert!(render(&result, Format::Json, Base::Hex).contains("\"zero_count\": 1092"));
+        assert_eq!(Base::Hex.format(0), "0x0");
+    }
+
+    #[test]
+    fn json_format_lists_result_fields() {
+        assert_eq!(
+            render(&sample(), Format::Json, Base::Decimal),
+            r#"{"zero_count": 6, "final_position": 32, "rotations": 10}"#
+        );
+    }
+
+    #[test]
+    fn kv_format_is_one_ordered_line() {
+        let result = SolveResult {
+            lines_skipped: 2,
+            ..sample()
+        };
+
+        assert_eq!(
+            render(&result, Format::Kv, Base::Hex),
+            "zeros=6 final=32 rotations=10 skipped=2"
+        );
+    }
+
+    #[test]
+    fn stats_json_combines_result_and_analytics() {
+        let result = SolveResult {
+            zero_fraction: 0.5,
+            left_distance: 2,
+            right_distance: 3,
+            ..sample()
+        };
+
+        assert_eq!(
+            stats_json(&result, &[1, 0, 4], 2),
+            r#"{"zero_count": 6, "passes_through_zero": 0, "stops_on_zero": 0, "final_position": 32, "rotations": 10, "clicks": 562, "zero_fraction": 0.5, "left_distance": 2, "right_distance": 3, "net_offset": 0, "full_revolutions": 0, "lines_skipped": 0, "visited_positions": 2, "histogram": [1, 0, 4]}"#
+        );
+    }
+
+    #[test]
+    fn summary_aligns_every_field() {
+        assert_eq!(
+            render_summary(&sample()),
+            "Rotations:       10\n\
+             Clicks:          562\n\
+             Zeros:           6\n\
+             Final position:  32\n\
+             Skipped lines:   0"
+        );
+    }
+
+    #[test]
+    fn fingerprint_is_stable_and_sensitive() {
+        assert_eq!(run_fingerprint(&sample()), 0xce26_5098_29a7_1329);
+        assert_eq!(
+            run_fingerprint(&SolveResult::default()),
+            0x81d2_3fd7_003c_2305
+        );
+
+        let other = SolveResult {
+            final_position: 33,
+            ..sample()
+        };
+        assert_ne!(run_fingerprint(&other), run_fingerprint(&sample()));
+
+        // Fields that are not part of the answer do not matter.
+        let warned = SolveResult {
+            lines_skipped: 3,
+            ..sample()
+        };
+        assert_eq!(run_fingerprint(&warned), run_fingerprint(&sample()));
+    }
+
+    #[test]
+    fn paint_wraps_text_in_ansi_codes() {
+        assert_eq!(paint("Password: 6", GREEN), "\x1b[32mPassword: 6\x1b[0m");
+    }
+
+    #[test]
+    fn small_dials_are_drawn_whole() {
+        assert_eq!(render_dial(2, 5), "0 1 [2] 3 4");
+        assert_eq!(render_dial(0, 1), "[0]");
+    }
+
+    #[test]
+    fn large_dials_show_the_neighborhood_with_wrap() {
+        assert_eq!(
+            render_dial(32, 100),
+            "... 27 28 29 30 31 [32] 33 34 35 36 37 ..."
+        );
+        assert_eq!(render_dial(2, 100), "... 97 98 99 0 1 [2] 3 4 5 6 7 ...");
+    }
+}
@@ -0,0 +1,133 @@
+use crate::puzzle_engine::Direction;
+use std::collections::HashMap;
+
+pub const DEFAULT_CAPACITY: usize = 256;
+
+type Key = (i32, Direction, i64);
+type Outcome = (i32, u64);
+
+// Least-recently-used cache of rotation outcomes for a single dial. Eviction
+// scans for the oldest entry, which is cheap at a few hundred entries.
+#[derive(Debug, Clone)]
+pub struct RotationCache {
+    capacity: usize,
+    clock: u64,
+    hits: u64,
+    entries: HashMap<Key, (Outcome, u64)>,
+}
+
+impl Default for RotationCache {
+    fn default() -> Self {
+        RotationCache::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl RotationCache {
+    pub fn new(capacity: usize) -> Self {
+        RotationCache {
+            capacity,
+            clock: 0,
+            hits: 0,
+            entries: HashMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Lookups answered without computing the outcome.
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn get_or_insert_with<F>(&mut self, key: Key, compute: F) -> Outcome
+    where
+        F: FnOnce() -> Outcome,
+    {
+        self.clock += 1;
+
+        if let Some((outcome, last_used)) = self.entries.get_mut(&key) {
+            *last_used = self.clock;
+            self.hits += 1;
+            return *outcome;
+        }
+
+        let outcome = compute();
+        if self.capacity == 0 {
+            return outcome;
+        }
+
+        if self.entries.len() >= self.capacity
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, last_used))| *last_used)
+                .map(|(key, _)| *key)
+        {
+            self.entries.remove(&oldest);
+        }
+
+        self.entries.insert(key, (outcome, self.clock));
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DialConfig;
+    use crate::puzzle_engine::{solve_puzzle, solve_puzzle_cached};
+
+    #[test]
+    fn computes_each_key_once() {
+        let mut cache = RotationCache::new(4);
+        let mut computed = 0;
+
+        for _ in 0..3 {
+            cache.get_or_insert_with((10, Direction::Right, 17), || {
+                computed += 1;
+                (27, 0)
+            });
+        }
+
+        assert_eq!(computed, 1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.hits(), 2);
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_entry() {
+        let mut cache = RotationCache::new(2);
+
+        cache.get_or_insert_with((0, Direction::Right, 1), || (1, 0));
+        cache.get_or_insert_with((1, Direction::Right, 1), || (2, 0));
+        cache.get_or_insert_with((0, Direction::Right, 1), || unreachable!());
+        cache.get_or_insert_with((2, Direction::Right, 1), || (3, 0));
+
+        assert_eq!(cache.len(), 2);
+        assert_eq!(
+            cache.get_or_insert_with((0, Direction::Right, 1), || unreachable!()),
+            (1, 0)
+        );
+        assert_eq!(
+            cache.get_or_insert_with((1, Direction::Right, 1), || (9, 9)),
+            (9, 9)
+        );
+    }
+
+    #[test]
+    fn cached_solve_matches_uncached() {
+        let input = "R17\n".repeat(1_000) + "L250\nR3\nDIAL 10\n" + &"R17\n".repeat(100);
+        let config = DialConfig::default();
+
+        assert_eq!(
+            solve_puzzle_cached(&input, &config),
+            solve_puzzle(&input, &config)
+        );
+    }
+}
@@ -0,0 +1,398 @@
+use crate::input_format::batch_segments;
+use crate::journal::JournalEntry;
+use crate::puzzle_engine::{
+    ConfigError, DialConfig, Interrupt, ParseError, Part, RunOptions, SolveResult, Step,
+    explain_with_options, print_step, solve_part_strict, solve_part_with_options,
+};
+use std::sync::atomic::AtomicBool;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SolverBuilder {
+    config: DialConfig,
+    part: Part,
+    verbose: bool,
+    progress: bool,
+    options: RunOptions,
+}
+
+impl SolverBuilder {
+    pub fn new() -> Self {
+        SolverBuilder::default()
+    }
+
+    pub fn config(mut self, config: DialConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn dial_size(mut self, size: u32) -> Self {
+        self.config.size = size;
+        self
+    }
+
+    pub fn start(mut self, start: i32) -> Self {
+        self.config.start = start;
+        self
+    }
+
+    pub fn target(mut self, target: i32) -> Self {
+        self.config.target = target;
+        self
+    }
+
+    pub fn count_initial(mut self, count_initial: bool) -> Self {
+        self.config.count_initial = count_initial;
+        self
+    }
+
+    pub fn degrees(mut self, degrees: bool) -> Self {
+        self.config.degrees = degrees;
+        self
+    }
+
+    pub fn scale(mut self, scale: u32) -> Self {
+        self.config.scale = scale;
+        self
+    }
+
+    pub fn part(mut self, part: Part) -> Self {
+        self.part = part;
+        self
+    }
+
+    pub fn verbose(mut self, verbose: bool) -> Self {
+        self.verbose = verbose;
+        self
+    }
+
+    // Report every 10% of the input's lines on stderr.
+    pub fn progress(mut self, progress: bool) -> Self {
+        self.progress = progress;
+        self
+    }
+
+    // Stop after this many applied rotations.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.options.limit = Some(limit);
+        self
+    }
+
+    // Stop once this many clicks have been stepped, partway through a rotation
+    // if need be; only the zeros crossed before the cutoff count.
+    pub fn max_clicks(mut self, max_clicks: u64) -> Self {
+        self.options.max_clicks = Some(max_clicks);
+        self
+    }
+
+    // Read a bare `L` or `R` as turning this far rather than as too short.
+    pub fn default_distance(mut self, distance: u32) -> Self {
+        self.options.default_distance = Some(i64::from(distance));
+        self
+    }
+
+    // Stop before the next rotation once `flag` is set, for example by a Ctrl-C
+    // handler. The result so far is exact for every rotation applied.
+    pub fn interrupt(mut self, flag: &'static AtomicBool) -> Self {
+        self.options.interrupt = Some(Interrupt(flag));
+        self
+    }
+
+    // Reject written distances outside `min..=max` with a warning.
+    pub fn distance_range(mut self, min: i64, max: i64) -> Self {
+        self.options.distance_range = Some((min, max));
+        self
+    }
+
+    // Silently drop rotations with an unknown direction, such as `U5`.
+    pub fn ignore_unknown(mut self, ignore_unknown: bool) -> Self {
+        self.options.ignore_unknown = ignore_unknown;
+        self
+    }
+
+    pub fn build(self) -> Result<Solver, ConfigError> {
+        self.config.validate()?;
+
+        Ok(Solver {
+            config: self.config,
+            part: self.part,
+            verbose: self.verbose,
+            progress: self.progress,
+            options: self.options,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Solver {
+    config: DialConfig,
+    part: Part,
+    verbose: bool,
+    progress: bool,
+    options: RunOptions,
+}
+
+impl Solver {
+    pub fn config(&self) -> &DialConfig {
+        &self.config
+    }
+
+    // Carries on from where `earlier` left the dial, its size included, as if
+    // both inputs were one. RESET still returns to the configured start.
+    pub fn continue_from(&self, earlier: &SolveResult) -> Solver {
+        let size = match earlier.dial_size {
+            0 => self.config.size,
+            size => size,
+        };
+        let position = earlier.final_position.rem_euclid(size as i32);
+
+        Solver {
+            options: RunOptions {
+                resume: Some((size, position)),
+                ..self.options
+            },
+            ..*self
+        }
+    }
+
+    // The dial the next run begins on, for walking the same input again.
+    pub fn starting_dial(&self) -> DialConfig {
+        match self.options.resume {
+            Some((size, position)) => DialConfig {
+                start: position,
+                ..self.config.resized(size)
+            },
+            None => self.config,
+        }
+    }
+
+    pub fn run(&self, input: &str) -> SolveResult {
+        solve_part_with_options(
+            input,
+            &self.config,
+            self.part,
+            self.options,
+            self.observer(input),
+        )
+    }
+
+    // Stops at the first rotation that fails to parse and reports its line.
+    pub fn run_strict(&self, input: &str) -> Result<SolveResult, (usize, ParseError)> {
+        solve_part_strict(
+            input,
+            &self.config,
+            self.part,
+            self.options,
+            self.observer(input),
+        )
+    }
+
+    // Like `run`, and also hands back every applied rotation for a journal.
+    pub fn run_recorded(&self, input: &str) -> (SolveResult, Vec<JournalEntry>) {
+        let mut entries = Vec::new();
+        let mut observe = self.observer(input);
+        let result = solve_part_with_options(
+            input,
+            &self.config,
+            self.part,
+            self.options,
+            |step: &Step| {
+                observe(step);
+                entries.push(JournalEntry::from_step(step));
+            },
+        );
+
+        (result, entries)
+    }
+
+    fn observer(&self, input: &str) -> impl FnMut(&Step) + '_ {
+        // Counting lines costs a pass over the input, so only do it when asked.
+        let lines = if self.progress {
+            input.lines().count()
+        } else {
+            0
+        };
+        let mut reported = 0;
+        move |step: &Step| {
+            if self.verbose {
+                print_step(step);
+            }
+            if self.progress {
+                let percent = step.line_number * 10 / lines.max(1) * 10;
+                if percent > reported {
+                    reported = percent;
+                    eprintln!("progress: {}%", percent);
+                }
+            }
+        }
+    }
+
+    pub fn run_batch(&self, input: &str) -> Vec<SolveResult> {
+        batch_segments(input)
+            .into_iter()
+            .map(|segment| self.run(segment))
+            .collect()
+    }
+
+    pub fn run_explained(&self, input: &str) -> (SolveResult, Vec<String>) {
+        explain_with_options(input, &self.config, self.part, self.options)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+
+    #[test]
+    fn default_builder_solves_part_two() {
+        let result = SolverBuilder::new().build().unwrap().run(EXAMPLE);
+        assert_eq!(result.zero_count, 6);
+        assert_eq!(result.final_position, 32);
+    }
+
+    #[test]
+    fn builder_configures_dial_and_part() {
+        let solver = SolverBuilder::new()
+            .dial_size(10)
+            .start(0)
+            .part(Part::One)
+            .build()
+            .unwrap();
+
+        let result = solver.run("R10\nL5\nR25\n");
+        assert_eq!(result.zero_count, 2);
+        assert_eq!(result.final_position, 0);
+    }
+
+    #[test]
+    fn continuing_keeps_the_size_set_by_an_earlier_dial_line() {
+        let solver = SolverBuilder::new().build().unwrap();
+        let whole = solver.run("R10\nDIAL 200\nR150\nL30\nR80\n");
+
+        let mut total = solver.run("R10\nDIAL 200\n");
+        total.merge(solver.continue_from(&total).run("R150\nL30\nR80\n"));
+
+        assert_eq!(total.dial_size, 200);
+        assert_eq!(total.zero_count, whole.zero_count);
+        assert_eq!(total.final_position, whole.final_position);
+    }
+
+    #[test]
+    fn explanations_describe_each_zero() {
+        let solver = SolverBuilder::new().build().unwrap();
+        let (result, explanations) = solver.run_explained("R73\nL5\nL250\n");
+
+        assert_eq!(result.zero_count, 4);
+        assert_eq!(
+            explanations,
+            vec![
+                "rotation R73 starting at 50 crossed zero once, ending at 23.",
+                "rotation L250 starting at 18 crossed zero 3 times, ending at 68.",
+            ]
+        );
+    }
+
+    #[test]
+    fn limit_stops_after_that_many_rotations() {
+        let solver = SolverBuilder::new().limit(2).build().unwrap();
+        let full = SolverBuilder::new().build().unwrap();
+
+        let result = solver.run("# warm up\nL68\n\nX1\nL30 R48\nL5\n");
+        assert_eq!(result, full.run("L68\nX1\nL30\n"));
+        assert_eq!(result.rotations_applied, 2);
+
+        let huge = solver.run("REPEAT 1000000000000 { R1 }\n");
+        assert_eq!(huge.rotations_applied, 2);
+
+        let (limited, explanations) = solver.run_explained(EXAMPLE);
+        assert_eq!(limited.rotations_applied, 2);
+        assert_eq!(explanations.len(), 1);
+    }
+
+    #[test]
+    fn click_budget_cuts_a_rotation_short() {
+        let solver = SolverBuilder::new().max_clicks(120).build().unwrap();
+
+        let result = solver.run("R20\nL200\nR5\n");
+        assert_eq!(result.clicks, 120);
+        assert_eq!(result.rotations_applied, 2);
+        assert_eq!(result.zero_count, 1);
+        assert_eq!(result.final_position, 70);
+
+        let exact = solver.run("R20 L100 R5\n");
+        assert_eq!((exact.rotations_applied, exact.final_position), (2, 70));
+        assert_eq!(solver.run("R50 L100\n").final_position, 30);
+    }
+
+    #[test]
+    fn interrupted_runs_keep_their_partial_result() {
+        static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+        let solver = SolverBuilder::new()
+            .interrupt(&INTERRUPTED)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            solver.run(EXAMPLE),
+            SolverBuilder::new().build().unwrap().run(EXAMPLE)
+        );
+
+        INTERRUPTED.store(true, std::sync::atomic::Ordering::Relaxed);
+        let stopped = solver.run("REPEAT 1000000000000 { R1 }\n");
+        assert_eq!(stopped.rotations_applied, 0);
+        assert_eq!(stopped.final_position, 50);
+    }
+
+    #[test]
+    fn out_of_range_distances_are_skipped() {
+        let solver = SolverBuilder::new().distance_range(1, 100).build().unwrap();
+        let result = solver.run("R50\nR500 L0\nL20\n");
+
+        assert_eq!(result.rotations_applied, 2);
+        assert_eq!(result.final_position, 80);
+        assert_eq!(result.lines_skipped, 2);
+        assert_eq!(
+            result.warnings[0].to_string(),
+            "Distance out of range in 'R500': expected 1 to 100"
+        );
+        assert!(matches!(
+            solver.run_strict("R50\nR101\n"),
+            Err((2, ParseError::DistanceOutOfRange { max: 100, .. }))
+        ));
+    }
+
+    #[test]
+    fn unknown_directions_can_be_ignored() {
+        let input = "R10\nU5\nL5 D3\nRq\n";
+        let warned = SolverBuilder::new().build().unwrap().run(input);
+        let ignored = SolverBuilder::new()
+            .ignore_unknown(true)
+            .build()
+            .unwrap()
+            .run(input);
+
+        assert_eq!(warned.warnings.len(), 3);
+        assert_eq!(ignored.lines_skipped, 1);
+        assert!(matches!(
+            ignored.warnings.as_slice(),
+            [ParseError::InvalidNumber { .. }]
+        ));
+        assert_eq!(ignored.final_position, warned.final_position);
+    }
+
+    #[test]
+    fn build_rejects_invalid_dials() {
+        assert_eq!(
+            SolverBuilder::new().dial_size(0).build(),
+            Err(ConfigError::EmptyDial)
+        );
+        assert!(matches!(
+            SolverBuilder::new().dial_size(10).start(10).build(),
+            Err(ConfigError::StartOutOfRange {
+                start: 10,
+                size: 10
+            })
+        ));
+    }
+}
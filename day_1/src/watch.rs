@@ -0,0 +1,94 @@
+use crate::cli::WarnLevel;
+use day_1::report::{self, Base, Format};
+use day_1::{Solver, errors_handler, file_reader};
+use std::thread::sleep;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+// Editors often save in several writes; wait until the file stops changing.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+// Modification time and length, or `None` while the file cannot be read.
+fn stamp(filename: &str) -> Option<(SystemTime, u64)> {
+    let metadata = std::fs::metadata(filename).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+}
+
+pub struct Watcher {
+    filename: String,
+    last: Option<(SystemTime, u64)>,
+}
+
+impl Watcher {
+    pub fn new(filename: &str) -> Self {
+        Watcher {
+            filename: filename.to_string(),
+            last: stamp(filename),
+        }
+    }
+
+    // True if the file has changed since the last call, once it has settled.
+    pub fn changed(&mut self) -> bool {
+        let mut current = stamp(&self.filename);
+        if current == self.last {
+            return false;
+        }
+
+        loop {
+            sleep(DEBOUNCE);
+            let settled = stamp(&self.filename);
+            if settled == current {
+                break;
+            }
+            current = settled;
+        }
+
+        self.last = current;
+        true
+    }
+}
+
+// Solves the file, then again after every change. Ctrl-C ends it like any
+// other run; there is no state to flush.
+pub fn watch(solver: &Solver, filename: &str, format: Format, base: Base, level: WarnLevel) -> ! {
+    let mut watcher = Watcher::new(filename);
+
+    loop {
+        match file_reader::read_input_file_auto(filename) {
+            Ok(input) => {
+                crate::note_skipped_lines(&input, level);
+                let result = solver.run(&input);
+                crate::report_warnings(&result.warnings, level, false);
+                println!("{}", report::render(&result, format, base));
+            }
+            Err(e) => eprintln!("{}", errors_handler::describe_file_error(&e, filename)),
+        }
+
+        while !watcher.changed() {
+            sleep(POLL_INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn changes_are_reported_once() {
+        let path = std::env::temp_dir().join(format!("day_1-watch-{}.txt", std::process::id()));
+        std::fs::write(&path, "R50\n").unwrap();
+        let filename = path.to_str().unwrap();
+
+        let mut watcher = Watcher::new(filename);
+        assert!(!watcher.changed());
+
+        std::fs::write(&path, "R50\nL10\n").unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+
+        std::fs::remove_file(&path).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+    }
+}
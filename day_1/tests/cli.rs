@@ -0,0 +1,547 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+const EXAMPLE: &str = "tests/fixtures/example.txt";
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_day_1"))
+        .args(args)
+        .output()
+        .expect("failed to run day_1")
+}
+
+#[test]
+fn expect_passes_on_matching_answer() {
+    let output = run(&["--expect", "6", EXAMPLE]);
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 6\n");
+}
+
+#[test]
+fn expect_fails_on_wrong_answer() {
+    let output = run(&["--expect", "7", EXAMPLE]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 6\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Expected 7 but computed 6"));
+}
+
+#[test]
+fn summary_lists_aggregate_fields() {
+    let output = run(&["--summary", EXAMPLE]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    for field in [
+        "Rotations:       10",
+        "Clicks:          462",
+        "Zeros:           6",
+        "Final position:  32",
+        "Skipped lines:   0",
+    ] {
+        assert!(
+            stdout.contains(field),
+            "missing '{}' in:\n{}",
+            field,
+            stdout
+        );
+    }
+    assert!(!stdout.contains("Password"));
+}
+
+#[test]
+fn color_always_highlights_answer_and_warnings() {
+    let output = run(&["--color", "always", "tests/fixtures/invalid.txt"]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "\x1b[32mPassword: 1\x1b[0m\n"
+    );
+    assert!(String::from_utf8_lossy(&output.stderr).starts_with("\x1b[31mWarning: "));
+}
+
+#[test]
+fn color_auto_and_never_keep_piped_output_plain() {
+    for choice in ["auto", "never"] {
+        let output = run(&["--color", choice, "tests/fixtures/invalid.txt"]);
+
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 1\n");
+        assert!(!String::from_utf8_lossy(&output.stderr).contains('\x1b'));
+    }
+}
+
+#[test]
+fn aoc_input_is_used_when_no_file_is_given() {
+    let output = Command::new(env!("CARGO_BIN_EXE_day_1"))
+        .env("AOC_INPUT", EXAMPLE)
+        .output()
+        .expect("failed to run day_1");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 6\n");
+}
+
+#[test]
+fn file_argument_overrides_aoc_input() {
+    let output = Command::new(env!("CARGO_BIN_EXE_day_1"))
+        .env("AOC_INPUT", "does-not-exist.txt")
+        .arg(EXAMPLE)
+        .output()
+        .expect("failed to run day_1");
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 6\n");
+}
+
+#[test]
+fn time_reports_solve_duration_on_stderr() {
+    let output = run(&["--time", EXAMPLE]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 6\n");
+    assert!(stderr.starts_with("solved in "), "{}", stderr);
+    assert!(stderr.trim_end().ends_with(" ms"), "{}", stderr);
+
+    assert!(run(&[EXAMPLE]).stderr.is_empty());
+}
+
+#[test]
+fn lenient_keeps_going_past_invalid_utf8() {
+    let path = std::env::temp_dir().join(format!("day_1-cli-lossy-{}.txt", std::process::id()));
+    std::fs::write(&path, b"R50\n\xC3(5\nL10\n").unwrap();
+    let filename = path.to_str().unwrap();
+
+    let strict = run(&[filename]);
+    let lenient = run(&["--lenient", filename]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(strict.status.code(), Some(1));
+    assert!(String::from_utf8_lossy(&strict.stderr).contains("could not be decoded"));
+
+    assert!(lenient.status.success());
+    assert_eq!(String::from_utf8_lossy(&lenient.stdout), "Password: 1\n");
+    assert!(String::from_utf8_lossy(&lenient.stderr).contains("Invalid direction"));
+}
+
+#[test]
+fn explain_prints_a_sentence_per_zero() {
+    let path = std::env::temp_dir().join(format!("day_1-explain-{}.txt", std::process::id()));
+    std::fs::write(&path, "R73\nL250\n").unwrap();
+
+    let output = run(&["--explain", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "rotation R73 starting at 50 crossed zero once, ending at 23.\n\
+         rotation L250 starting at 23 crossed zero 3 times, ending at 73.\n\
+         Password: 4\n"
+    );
+}
+
+#[test]
+fn show_dial_marks_the_final_position() {
+    let output = run(&["--show-dial", EXAMPLE]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Password: 6\n... 27 28 29 30 31 [32] 33 34 35 36 37 ...\n"
+    );
+}
+
+#[test]
+fn show_dial_uses_the_size_set_by_a_dial_directive() {
+    let path = std::env::temp_dir().join(format!("day_1-cli-show-dial-{}.txt", std::process::id()));
+    std::fs::write(&path, "DIAL 200\nR50\n").unwrap();
+
+    let output = run(&["--show-dial", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    // Start 50 of 100 becomes 100 of 200, so R50 ends past the old dial's end.
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Password: 0\n... 145 146 147 148 149 [150] 151 152 153 154 155 ...\n"
+    );
+}
+
+#[test]
+fn explain_and_strict_are_rejected_together() {
+    let output = run(&["--explain", "--strict", EXAMPLE]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .contains("--explain cannot be combined with --strict")
+    );
+}
+
+#[test]
+fn fail_on_warning_exits_with_two_after_the_answer() {
+    let output = run(&["--fail-on-warning", "tests/fixtures/invalid.txt"]);
+
+    assert_eq!(output.status.code(), Some(2));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 1\n");
+    assert!(String::from_utf8_lossy(&output.stderr).contains("1 line(s) failed to parse"));
+
+    assert!(run(&["--fail-on-warning", EXAMPLE]).status.success());
+}
+
+#[test]
+fn limit_solves_only_the_first_rotations() {
+    let limited = run(&["--limit", "2", "--summary", EXAMPLE]);
+    let stdout = String::from_utf8_lossy(&limited.stdout);
+
+    assert!(limited.status.success());
+    assert!(stdout.contains("Rotations:       2"), "{}", stdout);
+    assert!(stdout.contains("Final position:  52"), "{}", stdout);
+
+    let across_files = run(&["--limit", "12", "--summary", EXAMPLE, EXAMPLE]);
+    assert!(String::from_utf8_lossy(&across_files.stdout).contains("Rotations:       12"));
+}
+
+#[test]
+fn max_clicks_cuts_the_run_short_across_files() {
+    let output = run(&["--max-clicks", "500", "--summary", EXAMPLE, EXAMPLE]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Clicks:          500"), "{}", stdout);
+    assert!(stdout.contains("Rotations:       11"), "{}", stdout);
+    assert!(stdout.contains("Final position:  94"), "{}", stdout);
+}
+
+#[test]
+fn stats_json_replaces_the_answer() {
+    let output = run(&["--stats-json", EXAMPLE]);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert!(output.status.success());
+    assert!(stdout.starts_with("{\"zero_count\": 6, "), "{}", stdout);
+    assert!(stdout.contains("\"final_position\": 32, "), "{}", stdout);
+    assert!(stdout.contains("\"left_distance\": 340, \"right_distance\": 122, "));
+    assert!(!stdout.contains("Password"));
+
+    let histogram = stdout.split("\"histogram\": [").nth(1).unwrap();
+    let counts: Vec<u64> = histogram
+        .trim_end()
+        .trim_end_matches("]}")
+        .split(", ")
+        .map(|count| count.parse().unwrap())
+        .collect();
+    assert_eq!(counts.len(), 100);
+    assert_eq!(counts.iter().sum::<u64>(), 462);
+}
+
+#[test]
+fn default_distance_fills_in_bare_directions() {
+    let path = std::env::temp_dir().join(format!("day_1-cli-bare-{}.txt", std::process::id()));
+    std::fs::write(&path, "R49\nR\nr\nL L\n").unwrap();
+    let filename = path.to_str().unwrap();
+
+    let rejected = run(&[filename]);
+    let padded = run(&["--default-distance", "1", "--summary", filename]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(String::from_utf8_lossy(&rejected.stderr).contains("Line too short: 'R'"));
+    assert_eq!(String::from_utf8_lossy(&rejected.stdout), "Password: 0\n");
+
+    let stdout = String::from_utf8_lossy(&padded.stdout);
+    assert!(padded.stderr.is_empty());
+    assert!(stdout.contains("Rotations:       5"), "{}", stdout);
+    assert!(stdout.contains("Zeros:           2"), "{}", stdout);
+    assert!(stdout.contains("Final position:  99"), "{}", stdout);
+}
+
+#[test]
+fn scale_matches_doubled_distances() {
+    let doubled: String = std::fs::read_to_string(EXAMPLE)
+        .unwrap()
+        .lines()
+        .map(|line| format!("{}{}\n", &line[..1], line[1..].parse::<u64>().unwrap() * 2))
+        .collect();
+    let path = std::env::temp_dir().join(format!("day_1-cli-doubled-{}.txt", std::process::id()));
+    std::fs::write(&path, doubled).unwrap();
+
+    let expected = run(&["--summary", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+    let scaled = run(&["--scale", "2", "--summary", EXAMPLE]);
+
+    assert!(scaled.status.success());
+    assert_eq!(scaled.stdout, expected.stdout);
+    assert!(String::from_utf8_lossy(&scaled.stdout).contains("Clicks:          924"));
+}
+
+#[test]
+fn distance_range_warns_and_can_fail_the_run() {
+    let path = std::env::temp_dir().join(format!("day_1-cli-range-{}.txt", std::process::id()));
+    std::fs::write(&path, "R50\nR500\nL20\n").unwrap();
+    let filename = path.to_str().unwrap();
+
+    let lenient = run(&["--distance-range", "1:100", filename]);
+    let failing = run(&["--distance-range", "1:100", "--fail-on-warning", filename]);
+    let unchecked = run(&["--fail-on-warning", filename]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(lenient.status.success());
+    assert_eq!(String::from_utf8_lossy(&lenient.stdout), "Password: 1\n");
+    assert!(String::from_utf8_lossy(&lenient.stderr).contains("Distance out of range in 'R500'"));
+
+    assert_eq!(failing.status.code(), Some(2));
+    assert_eq!(failing.stdout, lenient.stdout);
+    assert!(unchecked.status.success());
+}
+
+#[test]
+fn journal_records_every_rotation() {
+    let path =
+        std::env::temp_dir().join(format!("day_1-cli-journal-{}.ndjson", std::process::id()));
+    let output = run(&["--journal", path.to_str().unwrap(), EXAMPLE]);
+    let journal = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 6\n");
+    assert_eq!(journal.lines().count(), 10);
+    assert_eq!(
+        journal.lines().last(),
+        Some(r#"{"line": 10, "direction": "L", "distance": 82, "position": 32, "zeros": 1}"#)
+    );
+}
+
+#[test]
+fn kv_format_prints_fields_in_order() {
+    let output = run(&["--format", "kv", EXAMPLE]);
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "zeros=6 final=32 rotations=10 skipped=0\n"
+    );
+}
+
+#[test]
+fn warn_level_controls_what_is_reported() {
+    let path = std::env::temp_dir().join(format!("day_1-cli-warn-{}.txt", std::process::id()));
+    std::fs::write(&path, "# warm up\nR50\n\nX1\nL68\n").unwrap();
+    let filename = path.to_str().unwrap();
+
+    let stderr = |level: &str| {
+        let output = run(&["--warn-level", level, filename]);
+        assert_eq!(String::from_utf8_lossy(&output.stdout), "Password: 1\n");
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+    let (silent, normal, verbose) = (stderr("silent"), stderr("normal"), stderr("verbose"));
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(silent, "");
+    assert!(normal.contains("'X1'"));
+    assert!(!normal.contains("skipped"));
+    assert!(verbose.starts_with("line 1: skipped comment\nline 3: skipped blank line\n"));
+    assert!(verbose.contains("'X1'"));
+}
+
+#[test]
+fn repl_warnings_follow_warn_level() {
+    let stderr = |level: &str| {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_day_1"))
+            .args(["--repl", "--warn-level", level])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("failed to run day_1");
+        child.stdin.take().unwrap().write_all(b"X1\nR50\n").unwrap();
+        let output = child.wait_with_output().unwrap();
+        assert!(String::from_utf8_lossy(&output.stdout).ends_with("Password: 1\n"));
+        String::from_utf8_lossy(&output.stderr).into_owned()
+    };
+
+    assert_eq!(stderr("silent"), "");
+    assert!(stderr("normal").contains("'X1'"));
+}
+
+#[test]
+fn plain_and_gzipped_files_mix_in_one_run() {
+    let mixed = run(&[EXAMPLE, "tests/fixtures/example.txt.gz", EXAMPLE]);
+    let plain = run(&[EXAMPLE, EXAMPLE, EXAMPLE]);
+
+    assert!(mixed.status.success());
+    assert_eq!(mixed.stdout, plain.stdout);
+    assert_eq!(String::from_utf8_lossy(&mixed.stdout), "Password: 15\n");
+    assert!(
+        String::from_utf8_lossy(&mixed.stderr)
+            .contains("Subtotal for 'tests/fixtures/example.txt.gz': 4")
+    );
+}
+
+#[test]
+fn corrupt_gzip_aborts_the_run() {
+    let path =
+        std::env::temp_dir().join(format!("day_1-cli-corrupt-{}.txt.gz", std::process::id()));
+    std::fs::write(&path, b"\x1f\x8bnot really gzip").unwrap();
+    let filename = path.to_str().unwrap();
+
+    let output = run(&[EXAMPLE, filename]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Subtotal for 'tests/fixtures/example.txt': 6"));
+    assert!(stderr.contains(&format!("Error: '{}' could not be decoded", filename)));
+}
+
+#[test]
+fn canonical_dump_solves_to_the_same_answer() {
+    let dir = std::env::temp_dir();
+    let input = dir.join(format!("day_1-cli-expanded-{}.txt", std::process::id()));
+    let canonical = dir.join(format!("day_1-cli-canonical-{}.txt", std::process::id()));
+    std::fs::write(&input, "REPEAT 3 { L68 R48 }\n# skip\nR1..4 R2x2\nB150\n").unwrap();
+
+    let dump = run(&["--dump-canonical", input.to_str().unwrap()]);
+    std::fs::write(&canonical, &dump.stdout).unwrap();
+    let original = run(&[input.to_str().unwrap()]);
+    let replayed = run(&[canonical.to_str().unwrap()]);
+    std::fs::remove_file(&input).unwrap();
+    std::fs::remove_file(&canonical).unwrap();
+
+    assert!(dump.status.success());
+    assert_eq!(String::from_utf8_lossy(&dump.stdout).lines().count(), 14);
+    assert_eq!(replayed.stdout, original.stdout);
+}
+
+#[test]
+fn progress_goes_to_stderr_and_leaves_the_answer_alone() {
+    let plain = run(&["tests/fixtures/large.txt"]);
+    let output = run(&["--progress", "tests/fixtures/large.txt"]);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    assert!(output.status.success());
+    assert_eq!(output.stdout, plain.stdout);
+    assert!(stderr.contains("progress: 10%"), "{}", stderr);
+    assert!(stderr.contains("progress: 100%"), "{}", stderr);
+    assert_eq!(stderr.matches("progress: 50%").count(), 1);
+}
+
+#[test]
+fn ignore_unknown_silences_invalid_directions() {
+    let warned = run(&["tests/fixtures/invalid.txt"]);
+    let ignored = run(&["--ignore-unknown", "tests/fixtures/invalid.txt"]);
+
+    assert!(String::from_utf8_lossy(&warned.stderr).contains("Invalid direction"));
+    assert!(ignored.stderr.is_empty());
+    assert_eq!(ignored.stdout, warned.stdout);
+}
+
+#[test]
+fn fingerprint_is_printed_after_the_answer() {
+    let output = run(&["--fingerprint", EXAMPLE]);
+
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Password: 6\nFingerprint: ce26509829a71329\n"
+    );
+}
+
+#[test]
+fn batch_prints_one_line_per_puzzle() {
+    let path = std::env::temp_dir().join(format!("day_1-batch-{}.txt", std::process::id()));
+    std::fs::write(&path, "L68\nL30\nR48\n---\nR50\nR100\nL1\n").unwrap();
+
+    let output = run(&["--batch", path.to_str().unwrap()]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        "Puzzle 1: 2\nPuzzle 2: 2\n"
+    );
+}
+
+#[test]
+fn strict_aborts_on_the_first_invalid_line() {
+    let output = run(&["--strict", "tests/fixtures/invalid.txt"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(output.stdout.is_empty());
+    assert!(
+        String::from_utf8_lossy(&output.stderr)
+            .starts_with("Error: tests/fixtures/invalid.txt:2: Invalid direction")
+    );
+}
+
+#[test]
+fn output_writes_the_answer_to_a_file() {
+    let path = std::env::temp_dir().join(format!("day_1-output-{}.json", std::process::id()));
+    std::fs::write(&path, "stale contents that are longer than the answer").unwrap();
+
+    let output = run(&[
+        "--format",
+        "json",
+        "--output",
+        path.to_str().unwrap(),
+        EXAMPLE,
+    ]);
+    let written = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(
+        written,
+        "{\"zero_count\": 6, \"final_position\": 32, \"rotations\": 10}\n"
+    );
+
+    let failed = run(&["--output", "no/such/dir/answer.txt", EXAMPLE]);
+    assert_eq!(failed.status.code(), Some(1));
+    assert!(
+        String::from_utf8_lossy(&failed.stderr)
+            .starts_with("Error: Cannot write 'no/such/dir/answer.txt'")
+    );
+}
+
+#[test]
+fn dial_lines_carry_over_to_the_next_file() {
+    let dir = std::env::temp_dir();
+    let write = |name: &str, contents: &str| {
+        let path = dir.join(format!("day_1-cli-{}-{}.txt", name, std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        path.to_str().unwrap().to_string()
+    };
+    let shrink = write("shrink", "R10\nDIAL 10\nR3\n");
+    let grow = write("grow", "DIAL 200\nR70\n");
+    let next = write("next", "R4\n");
+
+    let shrunk = run(&["--summary", &shrink, &next]);
+    let grown = run(&["--summary", &grow, &next]);
+    for path in [&shrink, &grow, &next] {
+        std::fs::remove_file(path).unwrap();
+    }
+
+    let stdout = String::from_utf8_lossy(&shrunk.stdout);
+    assert!(stdout.contains("Zeros:           1"), "{}", stdout);
+    assert!(stdout.contains("Final position:  3"), "{}", stdout);
+    assert!(grown.status.success());
+    assert!(String::from_utf8_lossy(&grown.stdout).contains("Final position:  174"));
+}
+
+#[test]
+fn reset_in_a_later_file_returns_to_the_configured_start() {
+    let dir = std::env::temp_dir();
+    let first = dir.join(format!("day_1-cli-turn-{}.txt", std::process::id()));
+    let second = dir.join(format!("day_1-cli-reset-{}.txt", std::process::id()));
+    std::fs::write(&first, "R10\n").unwrap();
+    std::fs::write(&second, "RESET\n").unwrap();
+
+    let output = run(&[
+        "--summary",
+        first.to_str().unwrap(),
+        second.to_str().unwrap(),
+    ]);
+    std::fs::remove_file(&first).unwrap();
+    std::fs::remove_file(&second).unwrap();
+
+    assert!(String::from_utf8_lossy(&output.stdout).contains("Final position:  50"));
+}
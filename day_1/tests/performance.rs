@@ -0,0 +1,46 @@
+use day_1::generator::generate;
+use day_1::{DialConfig, solve_puzzle};
+use std::time::{Duration, Instant};
+
+// Generous enough for a debug build on a slow machine; an accidentally
+// quadratic solver misses it by orders of magnitude. Slow CI can relax it.
+const BOUND_ENV: &str = "DAY_1_PERF_BOUND_MS";
+const DEFAULT_BOUND: Duration = Duration::from_millis(200);
+
+const ROTATIONS: usize = 20_000;
+const SAMPLES: u32 = 3;
+
+fn bound() -> Duration {
+    match std::env::var(BOUND_ENV) {
+        Ok(value) => Duration::from_millis(
+            value
+                .parse()
+                .unwrap_or_else(|_| panic!("{} must be a number of milliseconds", BOUND_ENV)),
+        ),
+        Err(_) => DEFAULT_BOUND,
+    }
+}
+
+#[test]
+fn large_input_solves_within_the_bound() {
+    let input = generate(ROTATIONS, 2025);
+    let config = DialConfig::default();
+
+    // The fastest sample, so one descheduled run does not fail the gate.
+    let mut fastest = Duration::MAX;
+    for _ in 0..SAMPLES {
+        let started = Instant::now();
+        let result = solve_puzzle(&input, &config);
+        fastest = fastest.min(started.elapsed());
+        assert_eq!(result.rotations_applied, ROTATIONS);
+    }
+
+    assert!(
+        fastest < bound(),
+        "solving {} rotations took {:?}, over the {:?} bound (set {} to relax it)",
+        ROTATIONS,
+        fastest,
+        bound(),
+        BOUND_ENV
+    );
+}